@@ -1,176 +1,422 @@
-use crate::{endpoints, utils};
-use anyhow::Error;
-use json::JsonValue;
+use crate::config::HttpOptions;
+use crate::async_api::AsyncStarStableApi;
+use crate::download::{self, DownloadGameOptions, InstallPlan, ProgressHandler};
+use crate::error::Error;
+use crate::endpoints::Endpoints;
+use crate::verify::{self, VerifyOptions, VerifyReport};
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
-use std::fs::File;
-use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-/// Implementation of the `launcher-proxy` API.
+/// Launcher version used when neither `latest.yml` nor a previously cached version is available.
+pub const FALLBACK_LAUNCHER_VERSION: &str = "2.30.1";
+
+/// Blocking implementation of the `launcher-proxy` API, every call including file downloads is
+/// run on the wrapped `AsyncStarStableApi`. Calls panic inside a tokio runtime, use the
+/// `AsyncStarStableApi` from `as_async` there.
 #[allow(clippy::upper_case_acronyms)]
-pub struct StarStableApi;
+pub struct StarStableApi {
+    /// Async API the calls are forwarded to
+    inner: AsyncStarStableApi,
+
+    /// Runtime driving `inner`
+    runtime: tokio::runtime::Runtime,
+}
 
 /// Auth response.
 #[derive(Debug)]
 pub struct AuthResponse {
     /// The users Account ID.
-    pub(crate) user_id: String,
+    pub user_id: String,
 
     /// The users Launcher Hash, aka. Auth Token.
-    pub(crate) launcher_hash: String,
+    pub launcher_hash: String,
 
     /// The queue token for the user.
-    pub(crate) queue_token: String,
+    pub queue_token: String,
+}
+
+/// Extra verification step the auth back-end asks for before accepting a login.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Challenge {
+    /// Identifies the challenge, sent back along with the code
+    pub id: String,
+
+    /// What kind of code is asked for
+    #[serde(rename = "type", default)]
+    pub kind: ChallengeKind,
+
+    /// Explanation sent by the server, e.g. which address the code was mailed to
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// Kind of code a `Challenge` asks for.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChallengeKind {
+    /// Code mailed to the account email
+    Email,
+
+    /// Code from an authenticator app
+    Totp,
+
+    /// Any kind this launcher doesn't know about
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+impl std::fmt::Display for Challenge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.message {
+            Some(message) => write!(f, "{}", message),
+            None => match self.kind {
+                ChallengeKind::Email => write!(f, "Enter the verification code mailed to you"),
+                ChallengeKind::Totp => write!(f, "Enter the code from your authenticator app"),
+                ChallengeKind::Unknown => write!(f, "Enter the verification code"),
+            },
+        }
+    }
 }
 
+/// Release metadata the launcher bucket publishes for electron-updater in `latest.yml`.
 #[derive(Debug)]
+pub struct LauncherRelease {
+    /// Version of the launcher, e.g. `2.30.1`
+    pub version: String,
+
+    /// File name of the installer, relative to the `latest.yml`
+    pub path: String,
+
+    /// Base64 encoded SHA-512 of the installer
+    pub sha512: String,
+}
+
+/// Game server response.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct GameStatus {
     /// id of the server
-    pub(crate) id: i16,
+    pub id: i16,
 
-    /// region_id of the server
-    pub(crate) region_id: i8,
+    /// region of the server, sent as `regionId`
+    #[serde(rename = "regionId")]
+    pub region: Region,
 
     /// internal name for server
-    pub(crate) name: String,
+    pub name: String,
 
     /// public name for server
-    pub(crate) friendly_name: String,
+    pub friendly_name: String,
 
     /// online status for server
-    pub(crate) online: bool,
+    pub online: bool,
 
     /// is server down for game updates?
-    pub(crate) update_in_progress: bool,
+    pub update_in_progress: bool,
 
     /// icon, we're never going to use this lol
-    pub(crate) icon_url: String,
+    #[serde(default)]
+    pub icon_url: Option<String>,
 
-    /// response code, follows standard return code format?
-    pub(crate) message_code: i16,
+    /// notice shown by the official launcher for this server
+    pub message_code: MessageCode,
 
     /// current active version on the server
-    pub(crate) game_version: String,
+    pub game_version: String,
+}
+
+impl GameStatus {
+    /// Whether the server can be joined, is being updated or is down.
+    pub fn state(&self) -> ServerState {
+        if self.update_in_progress {
+            ServerState::Updating
+        } else if self.online {
+            ServerState::Online
+        } else {
+            ServerState::Offline
+        }
+    }
+}
+
+impl std::fmt::Display for GameStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} — {}: {}, v{}",
+            self.region,
+            self.friendly_name,
+            self.state(),
+            self.game_version
+        )?;
+        if self.message_code != MessageCode::None {
+            write!(f, " ({})", self.message_code)?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether a game server can be joined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerState {
+    Online,
+    Updating,
+    Offline,
+}
+
+impl std::fmt::Display for ServerState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ServerState::Online => "Online",
+            ServerState::Updating => "Updating",
+            ServerState::Offline => "Offline",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Region a game server is hosted for, the raw `regionId`. Which id stands for which region
+/// isn't documented anywhere, so it is shown as `region <id>` until the ids are confirmed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct Region(pub i8);
+
+impl std::fmt::Display for Region {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "region {}", self.0)
+    }
+}
+
+/// Notice attached to a game server, from `messageCode`. `0` means there is nothing to show,
+/// no other code is known yet, so they are kept as they are until their meaning is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(from = "i16")]
+pub enum MessageCode {
+    /// No notice
+    None,
+
+    /// Any code this launcher doesn't know about
+    Unknown(i16),
+}
+
+impl From<i16> for MessageCode {
+    fn from(code: i16) -> Self {
+        match code {
+            0 => MessageCode::None,
+            code => MessageCode::Unknown(code),
+        }
+    }
+}
+
+impl std::fmt::Display for MessageCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MessageCode::None => write!(f, "no message"),
+            MessageCode::Unknown(code) => write!(f, "message unknown({})", code),
+        }
+    }
+}
+
+/// `Manifest.json` of a game version, also stored as `manifest.json` in the install folder.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Which client build this manifest describes
+    pub client: ManifestClient,
+
+    /// Every file of the build, local manifests written before file lists existed have none
+    #[serde(default)]
+    pub files: Vec<ManifestFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestClient {
+    /// Name of the build, always `client`
+    pub name: String,
+
+    /// Version of the build, same as `GameStatus::game_version`
+    pub version: String,
+}
+
+/// Single file entry from a manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestFile {
+    /// Path relative to the install folder
+    pub path: String,
+
+    /// Size in bytes
+    #[serde(default)]
+    pub size: u64,
+
+    /// Hex encoded SHA-256 of the file contents
+    #[serde(default)]
+    pub hash: String,
 }
 
 impl StarStableApi {
-    // /**
-    //  * When Star Stable entertainment decides to fix their shit, we will implement it here
-    //  * but at the time they have not updated the repo tag since 2021
-    //  *
-    //  * {
-    //  *   versionInfo: {
-    //  *     version: '2.9.13',
-    //  *     files: [ [Object] ],
-    //  *     path: 'Star Stable Online Setup 2.9.13.exe',
-    //  *     sha512: 'EsB1RFEqivuG+w4+yulMS8BnkE8DndpwCnjbpYXD2Bg3f3f3oTU+AfMYthxx0BOH0cVIcYxlJl4I/6X0G6S4UA==',
-    //  *     releaseDate: '2021-12-15T09:19:31.282Z'
-    //  *   },
-    //  *   updateInfo: {
-    //  *     version: '2.9.13',
-    //  *     files: [ [Object] ],
-    //  *     path: 'Star Stable Online Setup 2.9.13.exe',
-    //  *     sha512: 'EsB1RFEqivuG+w4+yulMS8BnkE8DndpwCnjbpYXD2Bg3f3f3oTU+AfMYthxx0BOH0cVIcYxlJl4I/6X0G6S4UA==',
-    //  *     releaseDate: '2021-12-15T09:19:31.282Z'
-    //  *   }
-    //  * }
-    //  */
-    //
-    /// Attempts to get latest release tag.
+    /// Creates the API client, every request goes through one pooled HTTP client with the
+    /// default headers, timeouts and retries from `http`.
+    pub fn new(endpoints: Endpoints, http: &HttpOptions) -> Result<Self, Error> {
+        Ok(StarStableApi {
+            inner: AsyncStarStableApi::new(endpoints, http)?,
+            runtime: tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?,
+        })
+    }
+
+    /// The back-end URLs this client talks to.
+    pub fn endpoints(&self) -> &Endpoints {
+        self.inner.endpoints()
+    }
+
+    /// Sets how verification codes are obtained when the auth back-end issues a `Challenge`,
+    /// without a handler such logins fail with `LoginFailure::VerificationRequired`.
+    pub fn with_challenge_handler(
+        mut self,
+        handler: impl Fn(&Challenge) -> Result<String, Error> + Send + Sync + 'static,
+    ) -> Self {
+        self.inner = self.inner.with_challenge_handler(handler);
+        self
+    }
+
+    /// Sets where progress messages go, the API is silent without a logger.
+    pub fn with_logger(mut self, logger: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.inner = self.inner.with_logger(logger);
+        self
+    }
+
+    /// The async API behind this client.
+    pub fn as_async(&self) -> &AsyncStarStableApi {
+        &self.inner
+    }
+
+    /// Hands `message` to the logger, if there is one.
+    pub(crate) fn log(&self, message: &str) {
+        self.inner.log(message);
+    }
+
+    /// Resolves the launcher version sent along with the login.
+    /// Uses `version_override` if given, otherwise the version from the launcher `latest.yml`,
+    /// cached for a day and used when fetching fails. Never lower than
+    /// `FALLBACK_LAUNCHER_VERSION`, since `latest.yml` has been stuck at `2.9.13` since 2021.
     /// ## Returns
-    /// A `String` containing the latest tagged version of the launcher .
+    /// A `String` containing the launcher version.
     #[inline(always)]
-    pub fn get_latest_launcher_version() -> String {
-        println!("Grabbing Latest launcher version...");
-        // let client = reqwest::blocking::Client::new();
-        "2.30.1".to_string() // Hardcode it ig...
+    pub fn get_latest_launcher_version(&self, version_override: Option<&str>) -> String {
+        self.runtime
+            .block_on(self.inner.get_latest_launcher_version(version_override))
     }
 
-    /// Downloads the official launcher, adding this shortcut because who wants to go through the whole effort of opening the browser...
+    /// Downloads the official launcher, see `AsyncStarStableApi::download_official_launcher`.
+    /// ## Returns
+    /// `true` if the installer was verified, Error if it doesn't match a `latest.yml` describing it.
+    #[inline(always)]
+    pub fn download_official_launcher(&self, download_location: PathBuf) -> Result<bool, Error> {
+        self.runtime
+            .block_on(self.inner.download_official_launcher(download_location))
+    }
+
+    /// Fetches and parses the electron-updater `latest.yml` of the official launcher.
+    /// ## Returns
+    /// The release metadata, or Error if the file couldn't be fetched or lacks a required key.
+    #[inline(always)]
+    pub fn get_launcher_release(&self) -> Result<LauncherRelease, Error> {
+        self.runtime.block_on(self.inner.get_launcher_release())
+    }
+
+    /// Downloads a single game file listed in the remote manifest, checking it against the
+    /// manifest SHA-256 while it is being written.
+    /// `on_progress` is called with the amount of bytes of this file on disk so far.
     /// ## Returns
     /// Result <(), Error>
     #[inline(always)]
-    pub fn download_official_launcher(download_location: PathBuf) -> Result<(), Error> {
-        println!("Downloading official launcher...");
-        let client = reqwest::blocking::Client::new();
-        let response = &client
-            .get(
-                endpoints::LAUNCHER_FILES.to_owned()
-                    + "latest/Star%20Stable%20Online%20Setup.exe",
-            )
-            .header("User-Agent", endpoints::USER_AGENT)
-            .header("pragma", "no-cache")
-            .header("cache-control", "no-cache")
-            .send()
-            .expect("Couldn't send GET request!")
-            .bytes()
-            .expect("Couldn't get raw text data!");
-
-        let mut file = match File::create(&download_location) {
-            Err(why) => panic!("couldn't create {}", why),
-            Ok(file) => file,
-        };
+    pub fn download_game_file(
+        &self,
+        version_hash: &str,
+        file_path: &str,
+        expected_sha256: Option<&[u8]>,
+        download_location: &Path,
+        on_progress: &mut (dyn FnMut(u64) + Send),
+    ) -> Result<(), Error> {
+        self.runtime.block_on(self.inner.download_game_file(
+            version_hash,
+            file_path,
+            expected_sha256,
+            download_location,
+            on_progress,
+        ))
+    }
 
-        file.write_all(response).unwrap();
-        Ok(())
+    /// Downloads the official launcher installer, see `download::download_launcher`.
+    #[inline(always)]
+    pub fn download_launcher(&self, download_path: Option<PathBuf>) -> Result<bool, Error> {
+        self.runtime
+            .block_on(download::download_launcher(&self.inner, download_path))
+    }
+
+    /// Compares the install against the remote manifest, see `download::check_game`.
+    #[inline(always)]
+    pub fn check_game(&self, options: &DownloadGameOptions) -> Result<InstallPlan, Error> {
+        self.runtime.block_on(download::check_game(&self.inner, options))
+    }
+
+    /// Applies a plan from `check_game`, see `download::install_game`.
+    #[inline(always)]
+    pub fn install_game(
+        &self,
+        options: &DownloadGameOptions,
+        plan: &InstallPlan,
+        on_progress: &ProgressHandler<'_>,
+    ) -> Result<(), Error> {
+        self.runtime
+            .block_on(download::install_game(&self.inner, options, plan, on_progress))
+    }
+
+    /// Installs or updates the game, see `download::download_game`.
+    #[inline(always)]
+    pub fn download_game(
+        &self,
+        options: &DownloadGameOptions,
+        on_progress: &ProgressHandler<'_>,
+    ) -> Result<InstallPlan, Error> {
+        self.runtime
+            .block_on(download::download_game(&self.inner, options, on_progress))
+    }
+
+    /// Checks the installed game files, see `verify::verify_game`.
+    #[inline(always)]
+    pub fn verify_game(&self, options: &VerifyOptions) -> Result<VerifyReport, Error> {
+        self.runtime.block_on(verify::verify_game(&self.inner, options))
+    }
+
+    /// Re-downloads damaged game files, see `verify::repair_game`.
+    #[inline(always)]
+    pub fn repair_game(
+        &self,
+        options: &VerifyOptions,
+        report: &VerifyReport,
+        on_progress: &ProgressHandler<'_>,
+    ) -> Result<(), Error> {
+        self.runtime
+            .block_on(verify::repair_game(&self.inner, options, report, on_progress))
     }
 
     /// Downloads file manifest
     /// ## Returns
-    /// JsonValue with data or Error
+    /// Manifest with data or Error
     #[inline(always)]
-    pub fn get_remote_manifest(version_hash: String) -> Result<JsonValue, Error> {
-        println!("Grabbing Game status...");
-        let client = reqwest::blocking::Client::new();
-        match json::parse(
-            &client
-                .get(endpoints::GAME_FILES.to_owned() + version_hash.as_str() + "/Manifest.json")
-                .header("Content-Type", "application/json")
-                .header("User-Agent", endpoints::USER_AGENT)
-                .header("pragma", "no-cache")
-                .header("cache-control", "no-cache")
-                .send()
-                .expect("Couldn't send GET request!")
-                .text()
-                .expect("Couldn't get raw text response from the request!"),
-        ) {
-            Ok(manifest_data) => Ok(manifest_data),
-            Err(e) => Err(Error::from(e)),
-        }
+    pub fn get_remote_manifest(&self, version_hash: String) -> Result<Manifest, Error> {
+        self.runtime.block_on(self.inner.get_remote_manifest(version_hash))
     }
 
-    /// Fetches status for account bound server
+    /// Fetches status for account bound server, `token` being the Launcher Hash
     /// ## Returns
-    /// structure of GameStatus containing with relevant info
+    /// structure of GameStatus containing with relevant info. Errors with `Error::AuthRejected`
+    /// if the server no longer accepts the Launcher Hash.
     #[inline(always)]
-    pub fn get_game_server_data(token: String) -> Result<GameStatus, Error> {
-        println!("Grabbing Game status...");
-        let client = reqwest::blocking::Client::new();
-        let response = json::parse(
-            &client
-                .get(endpoints::GAME_SERVER_DATA.to_owned() + token.as_str())
-                .header("Content-Type", "application/json")
-                .header("User-Agent", endpoints::USER_AGENT)
-                .send()
-                .expect("Couldn't send GET request!")
-                .text()
-                .expect("Couldn't get raw text response from the request!"),
-        );
-
-        match response {
-            Ok(response) => Ok(GameStatus {
-                id: response["id"].as_i16().unwrap(),
-                region_id: response["regionId"].as_i8().unwrap(),
-                name: response["name"].to_string(),
-                friendly_name: response["friendlyName"].to_string(),
-                online: response["online"].as_bool().unwrap(),
-                update_in_progress: response["updateInProgress"].as_bool().unwrap(),
-                icon_url: response["iconUrl"].to_string(),
-                message_code: response["messageCode"].as_i16().unwrap(),
-                game_version: response["gameVersion"].to_string(),
-            }),
-            Err(e) => Err(Error::msg(format!("Could not get game server data: {}", e))),
-        }
+    pub fn get_game_server_data(&self, token: String) -> Result<GameStatus, Error> {
+        self.runtime.block_on(self.inner.get_game_server_data(token))
     }
 
     /// Attempts to log in.
@@ -179,94 +425,73 @@ impl StarStableApi {
     /// Errors if the API `success` value is false, or if there's an error with retrieving/sending
     /// data.
     #[inline(always)]
-    pub fn login(email: String, password: String) -> Result<AuthResponse, Error> {
-        let json = json::object! {
-            username: email,
-            password: password,
-            launcherVersion: Self::get_latest_launcher_version(),
-            launcherPlatform: "desktop",
-            clientOsRelease: "10.0.22621",
-            browserFamily: "Electron",
-            deviceId: utils::get_fake_device_id()
-        };
+    pub fn login(
+        &self,
+        email: String,
+        password: String,
+        launcher_version: Option<&str>,
+    ) -> Result<AuthResponse, Error> {
+        self.runtime
+            .block_on(self.inner.login(email, password, launcher_version))
+    }
 
-        println!("Grabbing Launcher Hash and User ID...");
-        let client = reqwest::blocking::Client::new();
-        let response = json::parse(
-            &client
-                .post(endpoints::AUTH_LOGIN)
-                .body(json.dump())
-                .header("Content-Type", "application/json")
-                .header("User-Agent", endpoints::USER_AGENT)
-                .send()
-                .expect("Couldn't send POST request!")
-                .text()
-                .expect("Couldn't get raw text response from the request!"),
-        );
-
-        match response {
-            Ok(response) => {
-                if response["success"].as_bool().unwrap() {
-                    // Success, get the queueToken and return.
-                    let launcher_hash = response["launcherHash"]
-                        .as_str()
-                        .expect("Couldn't find 'launcherHash'!")
-                        .to_owned();
-
-                    Ok(AuthResponse {
-                        user_id: response["accountId"].to_string(),
-                        launcher_hash: launcher_hash.to_owned(),
-                        queue_token: Self::get_queue_token(launcher_hash, client).unwrap(),
-                    })
-                } else {
-                    return Err(Error::msg("Could not get success data for Login request"));
-                }
-            }
-            Err(e) => Err(Error::msg(format!(
-                "Could not get response data for Login request: {}",
-                e
-            ))),
-        }
+    /// Sends the login request, answering verification challenges through the challenge handler.
+    /// Neither the login queue nor the game server are contacted.
+    /// ## Returns
+    /// The Account ID and Launcher Hash of the user.
+    #[inline(always)]
+    pub fn authenticate(
+        &self,
+        email: String,
+        password: String,
+        launcher_version: Option<&str>,
+    ) -> Result<(String, String), Error> {
+        self.runtime
+            .block_on(self.inner.authenticate(email, password, launcher_version))
     }
 
-    /// Attempts to get the queue token.
+    /// Logs in and fetches the status of the account bound server, without entering the login
+    /// queue.
     /// ## Returns
-    /// A `String` containing the token.
-    /// Panics if the API `success` value is `false`, or there's an error with retrieving/sending
-    /// data.
+    /// The game server status.
+    #[inline(always)]
+    pub fn login_status_only(
+        &self,
+        email: String,
+        password: String,
+        launcher_version: Option<&str>,
+    ) -> Result<GameStatus, Error> {
+        self.runtime
+            .block_on(self.inner.login_status_only(email, password, launcher_version))
+    }
+
+    /// Continues a previous login with its Account ID and Launcher Hash, the queue token and the
+    /// server status are requested at the same time.
+    /// ## Returns
+    /// The login and the game server status. Errors with `Error::AuthRejected` if the server no
+    /// longer accepts the Launcher Hash.
     #[inline(always)]
-    fn get_queue_token(
+    pub fn resume_session(
+        &self,
+        user_id: String,
         launcher_hash: String,
-        client: reqwest::blocking::Client,
-    ) -> Result<String, Error> {
-        println!("Grabbing Queue Token...");
-        let response = json::parse(
-            &client
-                .post(endpoints::AUTH_QUEUE_CREATE.to_owned() + &*launcher_hash)
-                .header("Content-Type", "application/json")
-                .header("User-Agent", endpoints::USER_AGENT)
-                .send()
-                .expect("Couldn't send POST request!")
-                .text()
-                .expect("Couldn't get raw text response from the request!"),
-        );
-
-        match response {
-            Ok(response) => {
-                if response["success"].as_bool().unwrap() {
-                    // Success, get the queueToken and return.
-                    Ok(response["queueToken"]
-                        .as_str()
-                        .expect("Couldn't find 'queueToken'!")
-                        .to_owned())
-                } else {
-                    return Err(Error::msg("Couldn't get queue token"));
-                }
-            }
-            Err(e) => Err(Error::msg(format!(
-                "Couldn't get queue token response: {}",
-                e
-            ))),
-        }
+    ) -> Result<(AuthResponse, GameStatus), Error> {
+        self.runtime
+            .block_on(self.inner.resume_session(user_id, launcher_hash))
+    }
+
+    /// Logs in and fetches the status of the account bound server, the queue token and the
+    /// server status are requested at the same time.
+    /// ## Returns
+    /// The login and the game server status, or the first error of either request.
+    #[inline(always)]
+    pub fn login_with_status(
+        &self,
+        email: String,
+        password: String,
+        launcher_version: Option<&str>,
+    ) -> Result<(AuthResponse, GameStatus), Error> {
+        self.runtime
+            .block_on(self.inner.login_with_status(email, password, launcher_version))
     }
 }
@@ -0,0 +1,748 @@
+use crate::api::{
+    AuthResponse, Challenge, GameStatus, LauncherRelease, Manifest, FALLBACK_LAUNCHER_VERSION,
+};
+use crate::config::HttpOptions;
+use crate::endpoints::{self, Endpoints};
+use crate::error::{Error, LoginFailure};
+use crate::{stream, utils};
+use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use base64::prelude::{Engine, BASE64_STANDARD};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+use std::fs::{self, File, OpenOptions};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Async implementation of the `launcher-proxy` API, for frontends already running a tokio
+/// runtime. `StarStableApi` wraps it for blocking callers.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone)]
+pub struct AsyncStarStableApi {
+    /// Pooled HTTP client shared by every request
+    client: reqwest::Client,
+
+    /// Back-end URLs
+    endpoints: Endpoints,
+
+    /// How often a failed request is retried
+    retries: u32,
+
+    /// Total time an API request may take, and the longest a download may go without data
+    read_timeout: Duration,
+
+    /// Obtains the code for a verification challenge during login
+    challenge_handler: Option<Arc<ChallengeHandler>>,
+
+    /// Receives progress messages, nothing is logged without one
+    logger: Option<Arc<Logger>>,
+}
+
+/// Callback answering a verification `Challenge` with its code.
+pub type ChallengeHandler = dyn Fn(&Challenge) -> Result<String, Error> + Send + Sync;
+
+/// Callback receiving progress messages, e.g. retries, the login queue position or which
+/// launcher version is sent.
+pub type Logger = dyn Fn(&str) + Send + Sync;
+
+/// How many challenges a single login may issue before giving up.
+const MAX_CHALLENGES: usize = 3;
+
+/// Body of the `AUTH_LOGIN` request.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LoginRequest {
+    username: String,
+    password: String,
+    launcher_version: String,
+    launcher_platform: &'static str,
+    client_os_release: &'static str,
+    browser_family: &'static str,
+    device_id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    challenge_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    verification_code: Option<String>,
+}
+
+/// `AUTH_LOGIN` response, the account fields are only present on success.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LoginResponse {
+    success: bool,
+
+    #[serde(default)]
+    launcher_hash: Option<String>,
+
+    #[serde(default, deserialize_with = "utils::deserialize_id")]
+    account_id: Option<String>,
+
+    /// Reason code of a failed login
+    #[serde(default)]
+    error: Option<String>,
+
+    /// Human readable explanation of a failed login
+    #[serde(default)]
+    message: Option<String>,
+
+    /// Present when `error` is `verification_required`
+    #[serde(default)]
+    challenge: Option<Challenge>,
+}
+
+/// `AUTH_QUEUE_CREATE` response.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QueueResponse {
+    success: bool,
+
+    /// Missing on older responses, which only ever handed out passed tokens
+    #[serde(default)]
+    passed_the_queue: Option<bool>,
+
+    #[serde(default)]
+    queue_token: Option<String>,
+
+    #[serde(default)]
+    queue_position: Option<u64>,
+
+    /// Seconds
+    #[serde(default)]
+    estimated_wait_time: Option<u64>,
+}
+
+/// Installer of the current official launcher, in `latest/` of `Endpoints::launcher_files`.
+const LAUNCHER_INSTALLER: &str = "Star Stable Online Setup.exe";
+
+/// How long a launcher version fetched from `latest.yml` is used before fetching it again.
+const LAUNCHER_VERSION_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Longest pause between two polls of the login queue.
+const QUEUE_POLL_MAX: Duration = Duration::from_secs(30);
+
+impl AsyncStarStableApi {
+    /// Creates the API client, every request goes through one pooled HTTP client with the
+    /// default headers, timeouts and retries from `http`.
+    pub fn new(endpoints: Endpoints, http: &HttpOptions) -> Result<Self, Error> {
+        let mut headers = HeaderMap::new();
+        headers.insert("pragma", HeaderValue::from_static("no-cache"));
+        headers.insert("cache-control", HeaderValue::from_static("no-cache"));
+
+        let client = reqwest::Client::builder()
+            .user_agent(endpoints::USER_AGENT)
+            .default_headers(headers)
+            .connect_timeout(Duration::from_secs(http.connect_timeout))
+            .build()?;
+
+        Ok(AsyncStarStableApi {
+            client,
+            endpoints,
+            retries: http.retries,
+            read_timeout: Duration::from_secs(http.read_timeout),
+            challenge_handler: None,
+            logger: None,
+        })
+    }
+
+    /// Sets how verification codes are obtained when the auth back-end issues a `Challenge`,
+    /// without a handler such logins fail with `LoginFailure::VerificationRequired`.
+    pub fn with_challenge_handler(
+        mut self,
+        handler: impl Fn(&Challenge) -> Result<String, Error> + Send + Sync + 'static,
+    ) -> Self {
+        self.challenge_handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// Sets where progress messages go, the API is silent without a logger.
+    pub fn with_logger(mut self, logger: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.logger = Some(Arc::new(logger));
+        self
+    }
+
+    /// The back-end URLs this client talks to.
+    pub fn endpoints(&self) -> &Endpoints {
+        &self.endpoints
+    }
+
+    /// Hands `message` to the logger, if there is one.
+    pub(crate) fn log(&self, message: &str) {
+        if let Some(logger) = &self.logger {
+            logger(message);
+        }
+    }
+
+    /// Sends a request, retrying connection errors, timeouts, `5xx` and `429` responses with an
+    /// exponential backoff. A `Retry-After` header in seconds is honoured.
+    async fn send(&self, request: RequestBuilder) -> Result<Response, Error> {
+        let mut attempt = 0;
+        loop {
+            let retry = match request.try_clone() {
+                Some(retry) => retry,
+                None => return Ok(request.send().await?),
+            };
+
+            match retry.send().await {
+                Ok(response) if attempt < self.retries && is_retryable(response.status()) => {
+                    let delay = retry_delay(attempt, response.headers());
+                    self.log(&format!(
+                        "{} answered {}, retrying in {:?}...",
+                        response.url(),
+                        response.status(),
+                        delay
+                    ));
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) if attempt < self.retries && (e.is_connect() || e.is_timeout()) => {
+                    let delay = retry_delay(attempt, &HeaderMap::new());
+                    self.log(&format!("{}, retrying in {:?}...", e, delay));
+                    tokio::time::sleep(delay).await;
+                }
+                result => return Ok(result?),
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Resolves the launcher version sent along with the login.
+    /// Uses `version_override` if given, otherwise the version from the launcher `latest.yml`,
+    /// which is cached and only fetched again once the cache is older than a day. If it can't be
+    /// fetched, the cached version is used even when it is older. The bucket's `latest.yml` has
+    /// been stuck at `2.9.13` since 2021, so a version below `FALLBACK_LAUNCHER_VERSION` is never
+    /// sent. The cache holds what `latest.yml` said, so a bad value is replaced by the next fetch,
+    /// or right away by deleting `launcher_version` from the cache folder.
+    /// ## Returns
+    /// A `String` containing the launcher version.
+    pub async fn get_latest_launcher_version(&self, version_override: Option<&str>) -> String {
+        if let Some(version) = version_override {
+            return version.to_owned();
+        }
+
+        let cache_path = utils::cache_dir().map(|dir| dir.join("launcher_version"));
+        let cached = cache_path.as_ref().and_then(|cache_path| {
+            let version = fs::read_to_string(cache_path).ok()?.trim().to_owned();
+            let age = fs::metadata(cache_path).ok()?.modified().ok()?.elapsed().ok();
+            (!version.is_empty()).then_some((version, age))
+        });
+
+        let version = match cached {
+            Some((version, Some(age))) if age < LAUNCHER_VERSION_MAX_AGE => version,
+            cached => {
+                self.log("Grabbing Latest launcher version...");
+                match self.get_launcher_release().await {
+                    Ok(release) => {
+                        if let Some(cache_path) = &cache_path {
+                            if let Err(e) = utils::write_to_file(cache_path, release.version.clone()) {
+                                self.log(&format!("Couldn't cache launcher version: {}", e));
+                            }
+                        }
+                        release.version
+                    }
+                    Err(e) => {
+                        self.log(&format!("Couldn't grab latest launcher version ({})", e));
+                        cached.map_or(FALLBACK_LAUNCHER_VERSION.to_owned(), |(version, _)| version)
+                    }
+                }
+            }
+        };
+
+        if utils::compare_versions(&version, FALLBACK_LAUNCHER_VERSION).is_lt() {
+            self.log(&format!(
+                "Launcher version {} is outdated, using {}",
+                version, FALLBACK_LAUNCHER_VERSION
+            ));
+            return FALLBACK_LAUNCHER_VERSION.to_owned();
+        }
+        version
+    }
+
+    /// Fetches and parses the electron-updater `latest.yml` of the official launcher.
+    /// ## Returns
+    /// The release metadata, or Error if the file couldn't be fetched or lacks a required key.
+    pub async fn get_launcher_release(&self) -> Result<LauncherRelease, Error> {
+        self.log("Grabbing launcher release info...");
+        let response = self
+            .send(
+                self.client
+                    .get(self.endpoints.launcher_files.to_owned() + "latest/latest.yml")
+                    .timeout(self.read_timeout),
+            )
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        let values = utils::parse_yaml_top_level(&response);
+        let value = |key: &str| match values.get(key) {
+            Some(value) if !value.is_empty() => Ok(value.to_owned()),
+            _ => Err(Error::InvalidResponse(format!("latest.yml is missing '{}'", key))),
+        };
+
+        Ok(LauncherRelease {
+            version: value("version")?,
+            path: value("path")?,
+            sha512: value("sha512")?,
+        })
+    }
+
+    /// Downloads the official launcher, adding this shortcut because who wants to go through the whole effort of opening the browser...
+    /// The current `Star Stable Online Setup.exe` is checked against the sha512 from `latest.yml`
+    /// when the yml describes that file. The bucket has kept `2.9.13` in there since 2021, in that
+    /// case the sha512 belongs to another build and the installer is kept unverified.
+    /// ## Returns
+    /// `true` if the installer was verified, Error if it doesn't match a `latest.yml` describing it.
+    pub async fn download_official_launcher(&self, download_location: PathBuf) -> Result<bool, Error> {
+        let release = self.get_launcher_release().await?;
+        let verified = release.path == LAUNCHER_INSTALLER;
+        let expected_sha512 = if verified {
+            Some(BASE64_STANDARD.decode(&release.sha512).map_err(|e| {
+                Error::InvalidResponse(format!(
+                    "Invalid sha512 '{}' in latest.yml: {}",
+                    release.sha512, e
+                ))
+            })?)
+        } else {
+            None
+        };
+
+        self.log("Downloading official launcher...");
+        self.download_resumable::<Sha512>(
+            &(self.endpoints.launcher_files.to_owned()
+                + "latest/"
+                + &LAUNCHER_INSTALLER.replace(' ', "%20")),
+            &download_location,
+            expected_sha512.as_deref(),
+            &mut |_| (),
+        )
+        .await?;
+
+        if verified {
+            self.log(&format!(
+                "Launcher matches the sha512 of {} from latest.yml",
+                release.version
+            ));
+        } else {
+            self.log(&format!(
+                "latest.yml describes '{}' ({}), not '{}', the installer could not be verified",
+                release.path, release.version, LAUNCHER_INSTALLER
+            ));
+        }
+        Ok(verified)
+    }
+
+    /// Downloads a single game file listed in the remote manifest, checking it against the
+    /// manifest SHA-256 while it is being written.
+    /// `on_progress` is called with the amount of bytes of this file on disk so far.
+    /// ## Returns
+    /// Result <(), Error>
+    pub async fn download_game_file(
+        &self,
+        version_hash: &str,
+        file_path: &str,
+        expected_sha256: Option<&[u8]>,
+        download_location: &Path,
+        on_progress: &mut (dyn FnMut(u64) + Send),
+    ) -> Result<(), Error> {
+        if let Some(parent) = download_location.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        self.download_resumable::<Sha256>(
+            &(self.endpoints.game_files.to_owned() + version_hash + "/" + file_path),
+            download_location,
+            expected_sha256,
+            on_progress,
+        )
+        .await
+    }
+
+    /// Streams `url` into a `.part` file next to `download_location`, hashing it on the fly, and
+    /// moves it in place once complete and matching `expected_digest`. If a `.part` file is left
+    /// over from an interrupted download, only the missing bytes are requested with a `Range`
+    /// header. The download has no total deadline, it only fails with `Error::Timeout` once no
+    /// data arrived for the read timeout.
+    /// ## Returns
+    /// Result <(), Error>
+    async fn download_resumable<D: Digest>(
+        &self,
+        url: &str,
+        download_location: &Path,
+        expected_digest: Option<&[u8]>,
+        on_progress: &mut (dyn FnMut(u64) + Send),
+    ) -> Result<(), Error> {
+        let part_location = utils::part_path(download_location);
+        let resume_from = match fs::metadata(&part_location) {
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        };
+
+        let mut request = self.client.get(url);
+        if resume_from > 0 {
+            request = request.header("Range", format!("bytes={}-", resume_from));
+        }
+
+        let mut hasher = D::new();
+        let mut response = self.send(request).await?;
+        match response.status() {
+            // The part file already holds every byte
+            StatusCode::RANGE_NOT_SATISFIABLE if resume_from > 0 => {
+                stream::hash_reader(&mut File::open(&part_location)?, &mut hasher)?;
+                on_progress(resume_from);
+            }
+            StatusCode::PARTIAL_CONTENT if resume_from > 0 => {
+                self.log(&format!(
+                    "Resuming '{}' from {} bytes",
+                    download_location.display(),
+                    resume_from
+                ));
+                let mut file = OpenOptions::new().read(true).append(true).open(&part_location)?;
+                stream::hash_reader(&mut (&mut file).take(resume_from), &mut hasher)?;
+                on_progress(resume_from);
+                stream::copy_response_hashed(
+                    &mut response,
+                    &mut file,
+                    &mut hasher,
+                    resume_from,
+                    self.read_timeout,
+                    on_progress,
+                )
+                .await?;
+            }
+            // Server ignored the range, start over
+            _ => {
+                response = response.error_for_status()?;
+                let mut file = File::create(&part_location)?;
+                stream::copy_response_hashed(
+                    &mut response,
+                    &mut file,
+                    &mut hasher,
+                    0,
+                    self.read_timeout,
+                    on_progress,
+                )
+                .await?;
+            }
+        }
+
+        let digest = hasher.finalize();
+        if let Some(expected_digest) = expected_digest {
+            if digest.as_slice() != expected_digest {
+                fs::remove_file(&part_location)?;
+                return Err(Error::Integrity(format!(
+                    "Hash mismatch for '{}', expected {} but got {}",
+                    download_location.display(),
+                    hex::encode(expected_digest),
+                    hex::encode(digest)
+                )));
+            }
+        }
+
+        fs::rename(&part_location, download_location)?;
+        Ok(())
+    }
+
+    /// Downloads file manifest
+    /// ## Returns
+    /// Manifest with data or Error
+    pub async fn get_remote_manifest(&self, version_hash: String) -> Result<Manifest, Error> {
+        self.log("Grabbing remote manifest...");
+        let response = self
+            .send(
+                self.client
+                    .get(self.endpoints.game_files.to_owned() + version_hash.as_str() + "/Manifest.json")
+                    .header("Content-Type", "application/json")
+                    .timeout(self.read_timeout),
+            )
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        Self::parse_response("remote manifest", &response)
+    }
+
+    /// Fetches status for account bound server
+    /// ## Returns
+    /// structure of GameStatus containing with relevant info
+    pub async fn get_game_server_data(&self, token: String) -> Result<GameStatus, Error> {
+        self.log("Grabbing Game status...");
+        let response = self
+            .send(
+                self.client
+                    .get(self.endpoints.game_server_data.to_owned() + token.as_str())
+                    .header("Content-Type", "application/json")
+                    .timeout(self.read_timeout),
+            )
+            .await?;
+        let response = Self::check_launcher_hash(response)?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        Self::parse_response("game server", &response)
+    }
+
+    /// Attempts to log in.
+    /// ## Returns
+    /// A structure containing the User ID and Launcher Hash.
+    /// Errors if the API `success` value is false, or if there's an error with retrieving/sending
+    /// data.
+    pub async fn login(
+        &self,
+        email: String,
+        password: String,
+        launcher_version: Option<&str>,
+    ) -> Result<AuthResponse, Error> {
+        let (user_id, launcher_hash) = self.authenticate(email, password, launcher_version).await?;
+        Ok(AuthResponse {
+            user_id,
+            queue_token: self.get_queue_token(launcher_hash.clone()).await?,
+            launcher_hash,
+        })
+    }
+
+    /// Logs in and fetches the status of the account bound server, the queue token and the
+    /// server status are requested at the same time.
+    /// ## Returns
+    /// The login and the game server status, or the first error of either request.
+    pub async fn login_with_status(
+        &self,
+        email: String,
+        password: String,
+        launcher_version: Option<&str>,
+    ) -> Result<(AuthResponse, GameStatus), Error> {
+        let (user_id, launcher_hash) = self.authenticate(email, password, launcher_version).await?;
+        self.resume_session(user_id, launcher_hash).await
+    }
+
+    /// Logs in and fetches the status of the account bound server, without entering the login
+    /// queue. For callers that only need the status, e.g. status bars, which would otherwise wait
+    /// in the queue on release days.
+    /// ## Returns
+    /// The game server status.
+    pub async fn login_status_only(
+        &self,
+        email: String,
+        password: String,
+        launcher_version: Option<&str>,
+    ) -> Result<GameStatus, Error> {
+        let (_, launcher_hash) = self.authenticate(email, password, launcher_version).await?;
+        self.get_game_server_data(launcher_hash).await
+    }
+
+    /// Continues a previous login with its Account ID and Launcher Hash, the queue token and the
+    /// server status are requested at the same time.
+    /// ## Returns
+    /// The login and the game server status. Errors with `Error::AuthRejected` if the server no
+    /// longer accepts the Launcher Hash.
+    pub async fn resume_session(
+        &self,
+        user_id: String,
+        launcher_hash: String,
+    ) -> Result<(AuthResponse, GameStatus), Error> {
+        let (queue_token, game_status) = tokio::try_join!(
+            self.get_queue_token(launcher_hash.clone()),
+            self.get_game_server_data(launcher_hash.clone())
+        )?;
+
+        Ok((
+            AuthResponse {
+                user_id,
+                launcher_hash,
+                queue_token,
+            },
+            game_status,
+        ))
+    }
+
+    /// Sends the login request, answering verification challenges through the challenge handler.
+    /// Neither the login queue nor the game server are contacted.
+    /// ## Returns
+    /// The Account ID and Launcher Hash of the user.
+    pub async fn authenticate(
+        &self,
+        email: String,
+        password: String,
+        launcher_version: Option<&str>,
+    ) -> Result<(String, String), Error> {
+        let mut request = LoginRequest {
+            username: email,
+            password,
+            launcher_version: self.get_latest_launcher_version(launcher_version).await,
+            launcher_platform: "desktop",
+            client_os_release: "10.0.22621",
+            browser_family: "Electron",
+            device_id: utils::get_fake_device_id(),
+            challenge_id: None,
+            verification_code: None,
+        };
+
+        self.log("Grabbing Launcher Hash and User ID...");
+        let mut challenges = 0;
+        loop {
+            let response = self.send_login(&request).await?;
+            if response.success {
+                return Ok((
+                    Self::require_field(response.account_id, "login", "accountId")?,
+                    Self::require_field(response.launcher_hash, "login", "launcherHash")?,
+                ));
+            }
+
+            let reason = match &response.error {
+                Some(code) => LoginFailure::from_code(code),
+                None => LoginFailure::Unknown(None),
+            };
+            let message = response.message.filter(|message| !message.is_empty());
+            let (challenge, handler) = match (response.challenge, &self.challenge_handler) {
+                (Some(challenge), Some(handler))
+                    if reason == LoginFailure::VerificationRequired
+                        && challenges < MAX_CHALLENGES =>
+                {
+                    (challenge, handler)
+                }
+                _ => return Err(Error::Login { reason, message }),
+            };
+
+            challenges += 1;
+            request.verification_code = Some(handler(&challenge)?);
+            request.challenge_id = Some(challenge.id);
+        }
+    }
+
+    /// Posts a single login request.
+    async fn send_login(&self, request: &LoginRequest) -> Result<LoginResponse, Error> {
+        let response = self
+            .send(
+                self.client
+                    .post(&self.endpoints.auth_login)
+                    .body(serde_json::to_string(request)?)
+                    .header("Content-Type", "application/json")
+                    .timeout(self.read_timeout),
+            )
+            .await?;
+
+        // Failed logins come with a JSON body, a server error still there after the retries of
+        // `send` doesn't
+        if response.status().is_server_error() {
+            response.error_for_status_ref()?;
+        }
+
+        // Still limited after the retries of `send`
+        let rate_limited = response.status() == StatusCode::TOO_MANY_REQUESTS;
+        let response = response.text().await?;
+
+        let response: LoginResponse = match Self::parse_response("login", &response) {
+            Err(_) if rate_limited => {
+                return Err(Error::Login {
+                    reason: LoginFailure::RateLimited,
+                    message: None,
+                })
+            }
+            response => response?,
+        };
+        Ok(response)
+    }
+
+    /// Attempts to get the queue token. While `passedTheQueue` is `false` the queue is polled
+    /// again with a backoff, printing the queue position and estimated wait when given.
+    /// ## Returns
+    /// A `String` containing the token, once the queue has been passed.
+    /// Errors if the API `success` value is `false`, or there's an error with retrieving/sending
+    /// data.
+    async fn get_queue_token(&self, launcher_hash: String) -> Result<String, Error> {
+        self.log("Grabbing Queue Token...");
+        let mut attempt = 0;
+        loop {
+            let response = self
+                .send(
+                    self.client
+                        .post(self.endpoints.auth_queue_create.to_owned() + &*launcher_hash)
+                        .header("Content-Type", "application/json")
+                        .timeout(self.read_timeout),
+                )
+                .await?;
+            let response = Self::check_launcher_hash(response)?
+                .error_for_status()?
+                .text()
+                .await?;
+
+            let response: QueueResponse = Self::parse_response("queue", &response)?;
+            if !response.success {
+                return Err(Error::Queue("Couldn't get queue token".to_string()));
+            }
+
+            if response.passed_the_queue.unwrap_or(true) {
+                return Self::require_field(response.queue_token, "queue", "queueToken");
+            }
+
+            let delay = Duration::from_secs(2u64.pow(attempt.min(5))).min(QUEUE_POLL_MAX);
+            let mut waiting = "Waiting in login queue".to_string();
+            if let Some(position) = response.queue_position {
+                waiting += &format!(", position {}", position);
+            }
+            if let Some(estimated_wait) = response.estimated_wait_time {
+                waiting += &format!(", about {} left", format_wait(estimated_wait));
+            }
+            self.log(&format!("{}, checking again in {:?}...", waiting, delay));
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Turns a `401` or `403` to a request authenticated by the Launcher Hash into
+    /// `Error::AuthRejected`, so stale sessions can be told apart from other failures.
+    fn check_launcher_hash(response: Response) -> Result<Response, Error> {
+        match response.status() {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(Error::AuthRejected(format!(
+                "Launcher hash was rejected by {}",
+                response.url()
+            ))),
+            _ => Ok(response),
+        }
+    }
+
+    /// Deserializes a JSON response body, naming the response and the offending field on error.
+    fn parse_response<T: DeserializeOwned>(name: &str, body: &str) -> Result<T, Error> {
+        serde_json::from_str(body)
+            .map_err(|e| Error::InvalidResponse(format!("Could not parse {} response: {}", name, e)))
+    }
+
+    /// Unwraps a field that is only present on successful responses.
+    fn require_field<T>(value: Option<T>, name: &str, field: &str) -> Result<T, Error> {
+        value.ok_or_else(|| {
+            Error::InvalidResponse(format!(
+                "Could not parse {} response: missing field `{}`",
+                name, field
+            ))
+        })
+    }
+}
+
+/// Whether a response with `status` is worth retrying.
+fn is_retryable(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Backoff before retry number `attempt`, `500ms * 2^attempt` unless the response `headers`
+/// carry a `Retry-After` in seconds.
+fn retry_delay(attempt: u32, headers: &HeaderMap) -> Duration {
+    headers
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+        .map_or(Duration::from_millis(500 * 2u64.pow(attempt)), Duration::from_secs)
+}
+
+/// Formats a wait in seconds as e.g. `45s` or `3m 20s`.
+fn format_wait(seconds: u64) -> String {
+    match seconds {
+        0..=59 => format!("{}s", seconds),
+        _ => format!("{}m {}s", seconds / 60, seconds % 60),
+    }
+}
@@ -0,0 +1,110 @@
+use crate::endpoints::{EndpointOverrides, Endpoints};
+use crate::error::Error;
+use crate::utils;
+use serde::Deserialize;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Launcher configuration, read from `config.toml` in the user config folder, or the file given
+/// with `--config`.
+///
+/// ```toml
+/// launcher_version = "2.30.1"
+///
+/// [http]
+/// connect_timeout = 10
+/// read_timeout = 30
+/// retries = 3
+///
+/// # Login, queue and game server follow launcher_proxy unless they are set themselves
+/// [endpoints]
+/// launcher_proxy = "http://127.0.0.1:8080/"
+/// game_files = "http://127.0.0.1:8080/files/"
+/// launcher_files = "http://127.0.0.1:8080/launcher-files/"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Launcher version sent on login, `--launcher-version` takes precedence
+    pub launcher_version: Option<String>,
+
+    /// HTTP client settings
+    pub http: HttpOptions,
+
+    /// Back-end URLs, environment overrides are already applied
+    #[serde(skip)]
+    pub endpoints: Endpoints,
+
+    /// The `[endpoints]` table, `endpoints` is resolved from it
+    #[serde(rename = "endpoints")]
+    endpoint_overrides: EndpointOverrides,
+}
+
+/// Settings of the HTTP client shared by every request.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HttpOptions {
+    /// Seconds to wait for a connection to be established
+    pub connect_timeout: u64,
+
+    /// Seconds an API request may take in total, and the longest a download may go without
+    /// receiving data. Downloads themselves may take as long as they need
+    pub read_timeout: u64,
+
+    /// How often a request failing with a connection error, timeout, `5xx` or `429` is retried
+    pub retries: u32,
+}
+
+impl Default for HttpOptions {
+    fn default() -> Self {
+        HttpOptions {
+            connect_timeout: 10,
+            read_timeout: 30,
+            retries: 3,
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config from `path`, or from the default location if no path is given.
+    /// A missing default config is not an error, a missing explicit one is.
+    pub fn load(path: Option<&Path>) -> Result<Self, Error> {
+        let (path, required) = match path {
+            Some(path) => (Some(path.to_path_buf()), true),
+            None => (default_config_path(), false),
+        };
+
+        let mut config = match path {
+            None => Config::default(),
+            Some(path) => match fs::read_to_string(&path) {
+                Ok(data) => toml::from_str(&data).map_err(|e| {
+                    Error::InvalidResponse(format!(
+                        "Could not parse config '{}': {}",
+                        path.display(),
+                        e
+                    ))
+                })?,
+                Err(e) if e.kind() == ErrorKind::NotFound && !required => Config::default(),
+                Err(e) => return Err(Error::from(e)),
+            },
+        };
+
+        config.endpoint_overrides.apply_env();
+        config.endpoints = config.endpoint_overrides.resolve();
+        Ok(config)
+    }
+
+    /// Applies `overrides` on top of the config file and environment, e.g. from the command line.
+    pub fn override_endpoints(&mut self, overrides: &EndpointOverrides) {
+        self.endpoint_overrides.merge(overrides);
+        self.endpoints = self.endpoint_overrides.resolve();
+    }
+}
+
+/// `config.toml` in the launcher config folder, e.g. `%APPDATA%/sso-launcher-rs/config.toml`,
+/// see `SSO_CONFIG_DIR`.
+#[inline(always)]
+pub fn default_config_path() -> Option<PathBuf> {
+    utils::config_dir().map(|dir| dir.join("config.toml"))
+}
@@ -1,97 +1,437 @@
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
-use crate::api::{AuthResponse, GameStatus, StarStableApi};
-use crate::{DownloadGameArgs, DownloadLauncherArgs};
-use anyhow::Error;
-use json::{object, JsonValue};
+use std::io::{self, ErrorKind};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use crate::error::Error;
+use crate::api::{Manifest, ManifestClient, ManifestFile};
+use crate::async_api::AsyncStarStableApi;
+use futures_util::{stream, TryStreamExt};
 use crate::utils::write_to_file;
 
-const FULL_INSTALL: i8 = 0;
-const UPDATE: i8 = 1;
-const NON_PATCH_UPDATE: i8 = 2;
-const REPAIR: i8 = 3;
-const READY: i8 = 4;
-
-pub fn download_launcher(download_args: &DownloadLauncherArgs) -> Result<(), Error> {
-    let download = match download_args.download_path.to_owned() {
-        None => {
-            let path = dirs::home_dir()
-                .unwrap()
-                .as_path()
-                .join("Downloads")
-                .join("Star Stable Online Setup.exe");
-            StarStableApi::download_official_launcher(path)
+/// State of a local install compared to the remote manifest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallType {
+    /// Nothing is installed yet, every file has to be downloaded
+    FullInstall,
+    /// Installed version differs from the remote version
+    Update,
+    /// Same version, but the remote file list changed without a version bump
+    NonPatchUpdate,
+    /// Installed files don't match the manifest and have to be re-downloaded
+    Repair,
+    /// Install matches the remote manifest, nothing to do
+    Ready,
+}
+
+impl std::fmt::Display for InstallType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            InstallType::FullInstall => "full install",
+            InstallType::Update => "update",
+            InstallType::NonPatchUpdate => "non-patch update",
+            InstallType::Repair => "repair",
+            InstallType::Ready => "ready",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// How game files are transferred.
+#[derive(Debug, Clone)]
+pub struct TransferOptions {
+    /// How many game files are downloaded at the same time
+    pub concurrency: usize,
+
+    /// How many times a failed file download is retried before giving up
+    pub retries: u32,
+}
+
+impl Default for TransferOptions {
+    fn default() -> Self {
+        TransferOptions {
+            concurrency: 8,
+            retries: 3,
         }
-        Some(path) => StarStableApi::download_official_launcher(path),
-    };
+    }
+}
+
+/// What `download_game` installs and where.
+#[derive(Debug, Clone)]
+pub struct DownloadGameOptions {
+    /// Version to install, usually `GameStatus::game_version`
+    pub version: String,
+
+    /// The Star Stable Online base installation folder
+    pub install_path: PathBuf,
+
+    /// How files are transferred
+    pub transfer: TransferOptions,
+}
+
+/// What `install_game` has to do to bring an install to the wanted version, from `check_game`.
+#[derive(Debug)]
+pub struct InstallPlan {
+    /// State of the install compared to the remote manifest
+    pub install_type: InstallType,
+
+    /// Files to download and remove
+    pub diff: ManifestDiff,
+
+    /// Every file of the remote manifest, recorded as installed once the plan is applied
+    pub files: Vec<ManifestFile>,
+}
+
+/// Progress of a game file transfer, reported while `install_game` or `repair_game` run.
+#[derive(Debug, Clone)]
+pub enum DownloadProgress {
+    /// Transfer of `files` files with `bytes` bytes in total starts, `workers` at a time
+    Started {
+        files: usize,
+        bytes: u64,
+        workers: usize,
+    },
+
+    /// Another `bytes` bytes are on disk
+    Advanced { bytes: u64 },
+
+    /// The file at `path` is complete, `finished` files are done so far
+    FileFinished { path: String, finished: usize },
+
+    /// Downloading the file at `path` failed with `error` and is tried again
+    Retrying {
+        path: String,
+        attempt: u32,
+        retries: u32,
+        error: String,
+    },
+}
+
+/// Callback receiving the `DownloadProgress` of a transfer.
+pub type ProgressHandler<'a> = dyn Fn(DownloadProgress) + Send + Sync + 'a;
+
+/// Difference between the local and the remote manifest.
+#[derive(Debug, Default)]
+pub struct ManifestDiff {
+    /// Files that are missing locally
+    pub added: Vec<ManifestFile>,
+
+    /// Files whose contents changed between the manifests
+    pub changed: Vec<ManifestFile>,
+
+    /// Files that no longer exist in the remote manifest
+    pub removed: Vec<ManifestFile>,
+}
 
-    match download {
-        Ok(_) => Ok(()),
-        Err(e) => Err(Error::msg(format!("Failed to download launcher: {}", e))),
-    }
-}
-
-/// Update or download all required game files for given patch
-pub fn download_game(auth_response: AuthResponse, game_status: GameStatus, args: &DownloadGameArgs) -> Result<(), Error> {
-    let path = &args.install_path.clone().unwrap();
-    let version_hash = &args.version.clone();
-    let manifest_data = get_local_manifest(path);
-    let mut install_type = NON_PATCH_UPDATE;
-
-    match manifest_data {
-        Ok(data) => {
-            if (data == JsonValue::Null) {
-                install_type = FULL_INSTALL;
-                match version_hash {
-                    Some(version) => {
-                        match StarStableApi::get_remote_manifest(version.to_owned()) {
-                            Ok(remote_manifest) => {
-                                println!("{}", remote_manifest);
-                            }
-                            Err(e) => return Err(e),
-                        }
-                    },
-                    None => {
-                        match StarStableApi::get_remote_manifest(game_status.game_version.clone()) {
-                            Ok(remote_manifest) => {
-                                println!("{}", remote_manifest);
-                            }
-                            Err(e) => return Err(e),
-                        }
-                    }
+impl ManifestDiff {
+    /// Compares the files of the local manifest against the remote ones.
+    /// Files that are listed locally but are gone from disk count as added.
+    pub fn new(install_path: &Path, local: &[ManifestFile], remote: &[ManifestFile]) -> Self {
+        let local_files: HashMap<&str, &ManifestFile> =
+            local.iter().map(|file| (file.path.as_str(), file)).collect();
+        let remote_paths: HashSet<&str> = remote.iter().map(|file| file.path.as_str()).collect();
+        let mut diff = ManifestDiff::default();
+
+        for file in remote {
+            match local_files.get(file.path.as_str()) {
+                None => diff.added.push(file.clone()),
+                Some(_) if !install_path.join(&file.path).exists() => diff.added.push(file.clone()),
+                Some(local_file) if local_file.hash != file.hash || local_file.size != file.size => {
+                    diff.changed.push(file.clone())
                 }
-            } else {
-                if let Some(client_version) = data["client"]["version"].as_str() {
-                    if game_status.game_version.clone() == client_version {
-                        return Ok(());
-                    }
+                Some(_) => (),
+            }
+        }
+
+        diff.removed = local
+            .iter()
+            .filter(|file| !remote_paths.contains(file.path.as_str()))
+            .cloned()
+            .collect();
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+
+    /// Every file that has to be downloaded to apply this diff.
+    pub fn downloads(&self) -> impl Iterator<Item = &ManifestFile> {
+        self.added.iter().chain(self.changed.iter())
+    }
+}
+
+/// Downloads the official launcher installer to `download_path`, defaults to
+/// `Star Stable Online Setup.exe` in the Downloads folder of the user.
+/// ## Returns
+/// `true` if the installer was verified against `latest.yml`.
+pub async fn download_launcher(
+    api: &AsyncStarStableApi,
+    download_path: Option<PathBuf>,
+) -> Result<bool, Error> {
+    let path = match download_path {
+        None => match dirs::home_dir() {
+            Some(home) => home.join("Downloads").join("Star Stable Online Setup.exe"),
+            None => {
+                return Err(Error::Io(io::Error::new(
+                    ErrorKind::NotFound,
+                    "Couldn't find the home folder, pass --download-path instead",
+                )))
+            }
+        },
+        Some(path) => path,
+    };
+
+    match api.download_official_launcher(path.clone()).await {
+        Ok(verified) => Ok(verified),
+        Err(e) => Err(Error::Download {
+            path: path.display().to_string(),
+            source: Box::new(e),
+        }),
+    }
+}
+
+/// Compares the install against the remote manifest of `args.version`, without changing
+/// anything.
+/// ## Returns
+/// The install state and the files that have to be downloaded or removed.
+pub async fn check_game(api: &AsyncStarStableApi, args: &DownloadGameOptions) -> Result<InstallPlan, Error> {
+    let path = &args.install_path;
+    let local_manifest = get_local_manifest(path)?;
+    let remote_manifest = api.get_remote_manifest(args.version.clone()).await?;
+    let local_files = match &local_manifest {
+        Some(local_manifest) => local_manifest.files.as_slice(),
+        None => &[],
+    };
+    let diff = ManifestDiff::new(path, local_files, &remote_manifest.files);
+
+    Ok(InstallPlan {
+        install_type: install_type(local_manifest.as_ref(), &args.version, &diff),
+        diff,
+        files: remote_manifest.files,
+    })
+}
+
+/// Applies `plan` from `check_game`: downloads the added and changed files, removes the obsolete
+/// ones and records the new version. A `Ready` install is left alone.
+pub async fn install_game(
+    api: &AsyncStarStableApi,
+    args: &DownloadGameOptions,
+    plan: &InstallPlan,
+    on_progress: &ProgressHandler<'_>,
+) -> Result<(), Error> {
+    if plan.install_type == InstallType::Ready {
+        return Ok(());
+    }
+
+    let path = &args.install_path;
+    let downloads: Vec<&ManifestFile> = plan.diff.downloads().collect();
+    download_files(api, &args.version, path, &downloads, &args.transfer, on_progress).await?;
+
+    for file in &plan.diff.removed {
+        api.log(&format!("Removing obsolete file {}", file.path));
+        match fs::remove_file(install_file_path(path, &file.path)?) {
+            Err(e) if e.kind() != ErrorKind::NotFound => return Err(Error::from(e)),
+            _ => (),
+        }
+    }
+
+    // Only claim the new version once every file is on disk
+    store_local_manifest(path, &installed_manifest(args.version.clone(), plan.files.clone()))
+}
+
+/// Update or download all required game files for given patch, `check_game` followed by
+/// `install_game`.
+/// ## Returns
+/// The plan that was applied.
+pub async fn download_game(
+    api: &AsyncStarStableApi,
+    args: &DownloadGameOptions,
+    on_progress: &ProgressHandler<'_>,
+) -> Result<InstallPlan, Error> {
+    let plan = check_game(api, args).await?;
+    install_game(api, args, &plan, on_progress).await?;
+    Ok(plan)
+}
+
+/// Local manifest recording that `files` of `version_hash` are installed.
+pub fn installed_manifest(version_hash: String, files: Vec<ManifestFile>) -> Manifest {
+    Manifest {
+        client: ManifestClient {
+            name: "client".to_string(),
+            version: version_hash,
+        },
+        files,
+    }
+}
+
+/// Downloads the given manifest files into the install folder, at most `transfer.concurrency`
+/// at the same time. Each file is retried on failure, after the first file that fails for good
+/// the downloads still running are dropped, their `.part` files are resumed on the next run.
+pub async fn download_files(
+    api: &AsyncStarStableApi,
+    version_hash: &str,
+    install_path: &Path,
+    files: &[&ManifestFile],
+    transfer: &TransferOptions,
+    on_progress: &ProgressHandler<'_>,
+) -> Result<(), Error> {
+    if files.is_empty() {
+        return Ok(());
+    }
+
+    let workers = transfer.concurrency.clamp(1, files.len());
+    on_progress(DownloadProgress::Started {
+        files: files.len(),
+        bytes: files.iter().map(|file| file.size).sum(),
+        workers,
+    });
+
+    let finished = &AtomicUsize::new(0);
+    stream::iter(files.iter().map(Ok))
+        .try_for_each_concurrent(workers, |file| async move {
+            match download_file_with_retries(api, version_hash, install_path, file, transfer.retries, on_progress).await {
+                Ok(_) => {
+                    on_progress(DownloadProgress::FileFinished {
+                        path: file.path.clone(),
+                        finished: finished.fetch_add(1, Ordering::SeqCst) + 1,
+                    });
+                    Ok(())
                 }
+                Err(e) => Err(Error::Download {
+                    path: file.path.clone(),
+                    source: Box::new(e),
+                }),
             }
+        })
+        .await
+}
 
-            let client_json = object!{ "client" => object!{ "name" => "client", "version" => game_status.game_version.clone() } };
-            match store_local_manifest(&path, client_json) {
-                Ok(_) => Ok(()),
-                Err(e) => Err(e),
+/// Downloads a single manifest file, retrying with an exponential backoff. Written bytes are
+/// reported to `on_progress` as they arrive, a retry resumes where the failed attempt stopped.
+async fn download_file_with_retries(
+    api: &AsyncStarStableApi,
+    version_hash: &str,
+    install_path: &Path,
+    file: &ManifestFile,
+    retries: u32,
+    on_progress: &ProgressHandler<'_>,
+) -> Result<(), Error> {
+    let target = install_file_path(install_path, &file.path)?;
+    let expected_sha256 = match file.hash.as_str() {
+        "" => None,
+        hash => Some(hex::decode(hash).map_err(|e| {
+            Error::InvalidResponse(format!(
+                "Manifest hash of '{}' is not valid hex: {}",
+                file.path, e
+            ))
+        })?),
+    };
+
+    let mut reported = 0;
+    let mut on_file_progress = |position: u64| {
+        if position > reported {
+            on_progress(DownloadProgress::Advanced {
+                bytes: position - reported,
+            });
+            reported = position;
+        }
+    };
+
+    let mut attempt = 0;
+    loop {
+        match api
+            .download_game_file(
+                version_hash,
+                &file.path,
+                expected_sha256.as_deref(),
+                &target,
+                &mut on_file_progress,
+            )
+            .await
+        {
+            Ok(_) => break,
+            Err(e) if attempt < retries => {
+                attempt += 1;
+                on_progress(DownloadProgress::Retrying {
+                    path: file.path.clone(),
+                    attempt,
+                    retries,
+                    error: e.to_string(),
+                });
+                tokio::time::sleep(Duration::from_millis(500 * 2u64.pow(attempt - 1))).await;
             }
+            Err(e) => return Err(e),
         }
-        Err(e) => Err(e),
     }
+
+    // Keep the progress in line with the manifest if the served file had a different size
+    if file.size > reported {
+        on_progress(DownloadProgress::Advanced {
+            bytes: file.size - reported,
+        });
+    }
+    Ok(())
+}
+
+/// Works out which state the install is in, based on the local manifest and its diff to the
+/// remote manifest of `version_hash`.
+pub fn install_type(local_manifest: Option<&Manifest>, version_hash: &str, diff: &ManifestDiff) -> InstallType {
+    let local_manifest = match local_manifest {
+        Some(local_manifest) => local_manifest,
+        None => return InstallType::FullInstall,
+    };
+
+    if local_manifest.client.version != version_hash {
+        return InstallType::Update;
+    }
+
+    if diff.is_empty() {
+        return InstallType::Ready;
+    }
+
+    // Nothing changed remotely, installed files just went missing
+    if diff.changed.is_empty() && diff.removed.is_empty() && diff.added.iter().all(|file| local_manifest.files.contains(file)) {
+        return InstallType::Repair;
+    }
+
+    InstallType::NonPatchUpdate
 }
 
+/// Resolves a manifest file path inside the install folder, refusing paths that would escape it.
+pub(crate) fn install_file_path(install_path: &Path, file_path: &str) -> Result<PathBuf, Error> {
+    let relative = Path::new(file_path);
+    if relative
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_)))
+    {
+        return Err(Error::InvalidResponse(format!(
+            "Remote manifest contains invalid file path '{}'",
+            file_path
+        )));
+    }
+
+    Ok(install_path.join(relative))
+}
+
+/// Reads the `manifest.json` of the install folder.
+/// ## Returns
+/// The manifest, or `None` if nothing is installed yet.
 #[inline(always)]
-pub fn get_local_manifest(path: &PathBuf) -> Result<JsonValue, Error> {
-    let contents = fs::read_to_string(path.clone().join("manifest.json"));
+pub fn get_local_manifest(path: &Path) -> Result<Option<Manifest>, Error> {
+    let contents = fs::read_to_string(path.join("manifest.json"));
     match contents {
-        Ok(data) => match json::parse(data.as_str()) {
-            Ok(json) => Ok(json),
-            Err(e) => Err(Error::msg(format!(
+        Ok(data) => match serde_json::from_str(data.as_str()) {
+            Ok(manifest) => Ok(Some(manifest)),
+            Err(e) => Err(Error::InvalidResponse(format!(
                 "Could not parse JSON data from file: {}",
                 e
             ))),
         },
         Err(err) => {
-            if err.raw_os_error().unwrap() == 3 {
-                return Ok(JsonValue::Null);
+            if err.kind() == ErrorKind::NotFound {
+                return Ok(None);
             }
 
             Err(Error::from(err))
@@ -100,12 +440,9 @@ pub fn get_local_manifest(path: &PathBuf) -> Result<JsonValue, Error> {
 }
 
 #[inline(always)]
-pub fn store_local_manifest(path: &PathBuf, data: JsonValue) -> Result<(), Error> {
-    match write_to_file(
-        &path.clone().join("manifest.json"),
-        json::stringify_pretty(data, 4),
-    ) {
-        Ok(_) => Ok(()),
-        Err(err) => Err(Error::from(err))
-    }
+pub fn store_local_manifest(path: &Path, manifest: &Manifest) -> Result<(), Error> {
+    write_to_file(
+        &path.join("manifest.json"),
+        serde_json::to_string_pretty(manifest)?,
+    )
 }
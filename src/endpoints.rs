@@ -1,3 +1,6 @@
+use serde::Deserialize;
+use std::env;
+
 /// Login Auth back-end.
 /// ## Usage
 /// Uses JSON and takes these parameters:
@@ -10,7 +13,14 @@
 /// `deviceId`: String, leave empty
 /// ## Output
 /// A JSON Response, relevant data being `launcherHash`, `accountId`, `metricsUrl` and
-/// `metricsGroups`.
+/// `metricsGroups`. A failed login has `success` set to `false`, an `error` reason code such as
+/// `invalid_credentials`, `account_locked`, `email_not_verified`, `launcher_outdated`,
+/// `rate_limited` or `maintenance`, and a human readable `message`.
+///
+/// When the error is `verification_required`, a `challenge` object with an `id`, the `type` of
+/// code (`email` or `totp`) and an optional `message` is included. The login is then sent again
+/// with `challengeId` and `verificationCode` added, a wrong code fails with
+/// `invalid_verification_code`.
 ///
 /// Payload being sent from the bloatware launcher
 /// {
@@ -30,8 +40,11 @@ pub const AUTH_LOGIN: &str = "https://launcher-proxy.starstable.com/launcher/aut
 /// ## Output
 /// A JSON Response, relevant data being:
 /// `success`: bool
-/// `passedTheQueue`: bool,
+/// `passedTheQueue`: bool, the token is only valid once this is `true`, until then the endpoint
+/// is polled again
 /// `queueToken`: String
+/// `queuePosition`: Number, optional, place in the queue while waiting
+/// `estimatedWaitTime`: Number, optional, estimated wait in seconds
 pub const AUTH_QUEUE_CREATE: &str =
     "https://launcher-proxy.starstable.com/launcher/login-queue/v2/desktop/";
 
@@ -42,9 +55,23 @@ pub const LAUNCHER_PROXY: &str = "https://launcher-proxy.starstable.com/";
 pub const GAME_SERVER_DATA: &str = "https://launcher-proxy.starstable.com/launcher/game-server/";
 
 /// Manifest download endpoint
+/// ## Usage
+/// `<version_hash>/Manifest.json` lists every file of the client build, each file is then served
+/// from `<version_hash>/<path>`.
+/// ## Output
+/// A JSON Response, relevant data being:
+/// `client`: Object with `name` and `version`
+/// `files`: Array of objects with `path` (relative to the install folder), `size` and `hash`
+/// (hex encoded SHA-256)
 pub const GAME_FILES: &str = "https://sso-released-prod.starstable.com/";
 
 /// Launcher version/download endpoint
+/// ## Usage
+/// `latest/latest.yml` is the electron-updater release info, relevant data being `version`,
+/// `path` (installer file name, relative to `latest/`) and `sha512` (base64 encoded).
+/// The current installer is always `latest/Star Stable Online Setup.exe`, `latest.yml` has been
+/// stuck at `2.9.13` (`Star Stable Online Setup 2.9.13.exe`) since 2021, so its `sha512` only
+/// applies to the installer when `path` names it.
 pub const LAUNCHER_FILES: &str = "https://launcher-release-prod.starstable.com/";
 
 /// User Agent retrieved via `navigator.userAgent`.
@@ -52,3 +79,137 @@ pub const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWeb
 
 /// URL for metrics, don't touch this. Hardcoded in Electron based launcher
 pub const METRICS: &str = "https://metrics.starstable.com/metric/v1/metrics/";
+
+/// Set of back-end URLs the launcher talks to, defaults to the production URLs above.
+/// Built from `EndpointOverrides`, see there for how each URL can be changed.
+#[derive(Debug, Clone)]
+pub struct Endpoints {
+    /// See `AUTH_LOGIN`
+    pub auth_login: String,
+
+    /// See `AUTH_QUEUE_CREATE`
+    pub auth_queue_create: String,
+
+    /// See `LAUNCHER_PROXY`
+    pub launcher_proxy: String,
+
+    /// See `GAME_SERVER_DATA`
+    pub game_server_data: String,
+
+    /// See `GAME_FILES`
+    pub game_files: String,
+
+    /// See `LAUNCHER_FILES`
+    pub launcher_files: String,
+
+    /// See `METRICS`
+    pub metrics: String,
+}
+
+impl Default for Endpoints {
+    fn default() -> Self {
+        Endpoints {
+            auth_login: AUTH_LOGIN.to_string(),
+            auth_queue_create: AUTH_QUEUE_CREATE.to_string(),
+            launcher_proxy: LAUNCHER_PROXY.to_string(),
+            game_server_data: GAME_SERVER_DATA.to_string(),
+            game_files: GAME_FILES.to_string(),
+            launcher_files: LAUNCHER_FILES.to_string(),
+            metrics: METRICS.to_string(),
+        }
+    }
+}
+
+/// Back-end URLs set in the `[endpoints]` table of the config file, with its
+/// `SSO_<NAME>_URL` environment variable (e.g. `SSO_GAME_FILES_URL`) or on the command line.
+/// Every URL left unset falls back to its production default, except for `auth_login`,
+/// `auth_queue_create` and `game_server_data`, which live under `launcher_proxy` and follow it.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct EndpointOverrides {
+    /// See `AUTH_LOGIN`
+    pub auth_login: Option<String>,
+
+    /// See `AUTH_QUEUE_CREATE`
+    pub auth_queue_create: Option<String>,
+
+    /// See `LAUNCHER_PROXY`
+    pub launcher_proxy: Option<String>,
+
+    /// See `GAME_SERVER_DATA`
+    pub game_server_data: Option<String>,
+
+    /// See `GAME_FILES`
+    pub game_files: Option<String>,
+
+    /// See `LAUNCHER_FILES`
+    pub launcher_files: Option<String>,
+
+    /// See `METRICS`
+    pub metrics: Option<String>,
+}
+
+impl EndpointOverrides {
+    /// Applies the `SSO_<NAME>_URL` environment overrides.
+    pub fn apply_env(&mut self) {
+        for (name, url) in [
+            ("SSO_AUTH_LOGIN_URL", &mut self.auth_login),
+            ("SSO_AUTH_QUEUE_CREATE_URL", &mut self.auth_queue_create),
+            ("SSO_LAUNCHER_PROXY_URL", &mut self.launcher_proxy),
+            ("SSO_GAME_SERVER_DATA_URL", &mut self.game_server_data),
+            ("SSO_GAME_FILES_URL", &mut self.game_files),
+            ("SSO_LAUNCHER_FILES_URL", &mut self.launcher_files),
+            ("SSO_METRICS_URL", &mut self.metrics),
+        ] {
+            if let Ok(value) = env::var(name) {
+                *url = Some(value);
+            }
+        }
+    }
+
+    /// Replaces every URL that is set in `other`.
+    pub fn merge(&mut self, other: &EndpointOverrides) {
+        for (url, other) in [
+            (&mut self.auth_login, &other.auth_login),
+            (&mut self.auth_queue_create, &other.auth_queue_create),
+            (&mut self.launcher_proxy, &other.launcher_proxy),
+            (&mut self.game_server_data, &other.game_server_data),
+            (&mut self.game_files, &other.game_files),
+            (&mut self.launcher_files, &other.launcher_files),
+            (&mut self.metrics, &other.metrics),
+        ] {
+            if other.is_some() {
+                url.clone_from(other);
+            }
+        }
+    }
+
+    /// Fills in the defaults and makes sure every URL ends with a `/`, since paths are appended
+    /// to them.
+    pub fn resolve(&self) -> Endpoints {
+        let url = |url: &Option<String>, default: String| {
+            let mut url = url.clone().unwrap_or(default);
+            if !url.ends_with('/') {
+                url.push('/');
+            }
+            url
+        };
+
+        let launcher_proxy = url(&self.launcher_proxy, LAUNCHER_PROXY.to_string());
+        Endpoints {
+            auth_login: url(&self.auth_login, launcher_proxy.clone() + "launcher/auth/"),
+            auth_queue_create: url(
+                &self.auth_queue_create,
+                launcher_proxy.clone() + "launcher/login-queue/v2/desktop/",
+            ),
+            game_server_data: url(
+                &self.game_server_data,
+                launcher_proxy.clone() + "launcher/game-server/",
+            ),
+            game_files: url(&self.game_files, GAME_FILES.to_string()),
+            launcher_files: url(&self.launcher_files, LAUNCHER_FILES.to_string()),
+            metrics: url(&self.metrics, METRICS.to_string()),
+            launcher_proxy,
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use std::io;
+use std::path::PathBuf;
+
+/// Every way the launcher can fail, each one maps to a stable process exit code so wrapper
+/// scripts can branch on why the launcher failed.
+///
+/// | Code | Variant            |
+/// |------|--------------------|
+/// | 2    | invalid arguments  |
+/// | 3    | `Network`          |
+/// | 3    | `Timeout`          |
+/// | 4    | `AuthRejected`     |
+/// | 4    | `Login` (wrong credentials or unknown reason) |
+/// | 5    | `Queue`            |
+/// | 6    | `ServerOffline`    |
+/// | 7    | `ServerUpdating`   |
+/// | 8    | `VersionMismatch`  |
+/// | 9    | `InstallMissing`   |
+/// | 10   | `Io`               |
+/// | 11   | `InvalidResponse`  |
+/// | 12   | `Integrity`        |
+/// | 13   | `Launch`           |
+/// | 14   | `Credentials`      |
+/// | 15   | `Login` (account locked)          |
+/// | 16   | `Login` (email not verified)      |
+/// | 17   | `Login` (launcher outdated)       |
+/// | 18   | `Login` (rate limited)            |
+/// | 19   | `Login` (maintenance)             |
+/// | 20   | `Login` (verification not passed) |
+///
+/// `Download` uses the code of the error that made the download fail.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Request couldn't be sent, timed out, or the server answered with an error status.
+    #[error("Network request failed: {0}")]
+    Network(#[from] reqwest::Error),
+
+    /// A download stalled, no data arrived for the read timeout.
+    #[error("Network request timed out: {0}")]
+    Timeout(String),
+
+    /// The back-end no longer accepts the Launcher Hash of a session.
+    #[error("{0}")]
+    AuthRejected(String),
+
+    /// The auth back-end refused the login, `message` is the explanation sent by the server.
+    #[error("{reason}{}", message.as_ref().map(|message| format!(" (server said: {})", message)).unwrap_or_default())]
+    Login {
+        reason: LoginFailure,
+        message: Option<String>,
+    },
+
+    /// No queue token could be created for the login.
+    #[error("{0}")]
+    Queue(String),
+
+    /// The game server is offline for a reason other than an update.
+    #[error("Game server '{server}' is not available at the time for unknown reason, please try again later. For more information see Star Stable Onlines's website")]
+    ServerOffline { server: String },
+
+    /// The game server is down for a game update.
+    #[error("Game server '{server}' undergoing update to version '{version}', please try again later")]
+    ServerUpdating { server: String, version: String },
+
+    /// The installed game version doesn't match the version on the server.
+    #[error("Game server '{server}' is not the same version '{server_version}' as installed version '{installed_version}', cannot join!")]
+    VersionMismatch {
+        server: String,
+        server_version: String,
+        installed_version: String,
+    },
+
+    /// The game, or a part of it, is not installed at the given path.
+    #[error("{0}")]
+    InstallMissing(String),
+
+    /// Reading or writing local files failed.
+    #[error("{0}")]
+    Io(#[from] io::Error),
+
+    /// A response or file didn't have the expected format.
+    #[error("{0}")]
+    InvalidResponse(String),
+
+    /// Downloaded or installed data doesn't match its expected hash.
+    #[error("{0}")]
+    Integrity(String),
+
+    /// The game executable couldn't be started.
+    #[error("Couldn't start '{}'!: {source}", exe.display())]
+    Launch { exe: PathBuf, source: io::Error },
+
+    /// No login password could be read from the chosen source.
+    #[error("{0}")]
+    Credentials(String),
+
+    /// Downloading a single file failed.
+    #[error("Failed to download '{path}': {source}")]
+    Download { path: String, source: Box<Error> },
+}
+
+impl Error {
+    /// Process exit code for this error, see the table on `Error`.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Network(_) | Error::Timeout(_) => 3,
+            Error::AuthRejected(_) => 4,
+            Error::Login { reason, .. } => reason.exit_code(),
+            Error::Queue(_) => 5,
+            Error::ServerOffline { .. } => 6,
+            Error::ServerUpdating { .. } => 7,
+            Error::VersionMismatch { .. } => 8,
+            Error::InstallMissing(_) => 9,
+            Error::Io(_) => 10,
+            Error::InvalidResponse(_) => 11,
+            Error::Integrity(_) => 12,
+            Error::Launch { .. } => 13,
+            Error::Credentials(_) => 14,
+            Error::Download { source, .. } => source.exit_code(),
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::InvalidResponse(e.to_string())
+    }
+}
+
+/// Why the auth back-end refused a login, parsed from the `error` field of its response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoginFailure {
+    /// Email or password is wrong
+    WrongCredentials,
+
+    /// The account is locked or banned
+    AccountLocked,
+
+    /// The email address of the account hasn't been verified yet
+    EmailNotVerified,
+
+    /// The launcher version sent on login is no longer accepted
+    LauncherOutdated,
+
+    /// Too many login attempts in a short time
+    RateLimited,
+
+    /// Logins are disabled for maintenance
+    Maintenance,
+
+    /// The server asks for a verification code and none could be given
+    VerificationRequired,
+
+    /// The verification code was wrong or expired
+    VerificationFailed,
+
+    /// Any other reason, holds the `error` code if the server sent one
+    Unknown(Option<String>),
+}
+
+impl LoginFailure {
+    /// Maps the `error` code of a failed login response.
+    pub fn from_code(code: &str) -> Self {
+        match code.to_ascii_lowercase().as_str() {
+            "invalid_credentials" | "wrong_credentials" | "invalid_password" | "user_not_found" => {
+                LoginFailure::WrongCredentials
+            }
+            "account_locked" | "account_banned" | "account_suspended" => LoginFailure::AccountLocked,
+            "email_not_verified" | "email_verification_required" => LoginFailure::EmailNotVerified,
+            "launcher_outdated" | "unsupported_launcher_version" => LoginFailure::LauncherOutdated,
+            "rate_limited" | "too_many_attempts" => LoginFailure::RateLimited,
+            "maintenance" | "service_unavailable" => LoginFailure::Maintenance,
+            "verification_required" | "two_factor_required" => LoginFailure::VerificationRequired,
+            "invalid_verification_code" | "verification_failed" => LoginFailure::VerificationFailed,
+            _ => LoginFailure::Unknown(Some(code.to_owned())),
+        }
+    }
+
+    /// Process exit code for this reason, see the table on `Error`.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            LoginFailure::WrongCredentials | LoginFailure::Unknown(_) => 4,
+            LoginFailure::AccountLocked => 15,
+            LoginFailure::EmailNotVerified => 16,
+            LoginFailure::LauncherOutdated => 17,
+            LoginFailure::RateLimited => 18,
+            LoginFailure::Maintenance => 19,
+            LoginFailure::VerificationRequired | LoginFailure::VerificationFailed => 20,
+        }
+    }
+}
+
+impl std::fmt::Display for LoginFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoginFailure::WrongCredentials => write!(f, "Wrong email or password"),
+            LoginFailure::AccountLocked => write!(
+                f,
+                "Account is locked or banned, contact Star Stable support"
+            ),
+            LoginFailure::EmailNotVerified => write!(
+                f,
+                "Email address isn't verified yet, follow the link in the verification mail first"
+            ),
+            LoginFailure::LauncherOutdated => write!(
+                f,
+                "Launcher version is outdated, pass a newer --launcher-version or remove launcher_version from the config"
+            ),
+            LoginFailure::RateLimited => write!(
+                f,
+                "Too many login attempts, wait a few minutes before trying again"
+            ),
+            LoginFailure::Maintenance => write!(f, "Login is down for maintenance, try again later"),
+            LoginFailure::VerificationRequired => write!(
+                f,
+                "Login needs a verification code, pass it with --code or --code-command"
+            ),
+            LoginFailure::VerificationFailed => {
+                write!(f, "Verification code was wrong or expired")
+            }
+            LoginFailure::Unknown(Some(code)) => write!(f, "Login rejected with '{}'", code),
+            LoginFailure::Unknown(None) => write!(f, "Login rejected for unknown reason"),
+        }
+    }
+}
@@ -1,15 +1,16 @@
-use crate::api::{AuthResponse, GameStatus};
+use crate::api::{AuthResponse, GameStatus, StarStableApi};
+use crate::endpoints::Endpoints;
 use crate::download::get_local_manifest;
-use crate::{endpoints, LaunchArgs};
-use anyhow::Error;
+use crate::error::Error;
 use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
 
 /// Launches the game using exe path, cwd, arguments and debug flag.
 fn _launch_game(
-    exe: &PathBuf,
-    launch_args: &Vec<String>,
+    api: &StarStableApi,
+    exe: &Path,
+    launch_args: &[String],
     cwd: &Path,
     debug: bool,
 ) -> Result<(), Error> {
@@ -21,8 +22,8 @@ fn _launch_game(
         .stderr(Stdio::piped())
         .spawn()
     {
-        Ok(mut child) => Ok({
-            if debug.clone() {
+        Ok(mut child) => {
+            if debug {
                 let mut stdout = child.stdout.take().expect("Failed to capture stdout");
                 let mut stderr = child.stderr.take().expect("Failed to capture stderr");
 
@@ -57,126 +58,203 @@ fn _launch_game(
                 });
 
                 // Wait for the child process to finish
-                let status = child.wait().expect("Failed to wait on child process");
-                println!("Child process exited with status: {}", status);
+                let status = child.wait()?;
+                api.log(&format!("Child process exited with status: {}", status));
 
                 // Wait for the threads to finish
                 stdout_thread.join().expect("Failed to join stdout thread");
                 stderr_thread.join().expect("Failed to join stderr thread");
-            } else {
-                return Ok(());
             }
+            Ok(())
+        }
+        Err(e) => Err(Error::Launch {
+            exe: exe.to_path_buf(),
+            source: e,
         }),
-        Err(e) => Err(Error::msg(format!(
-            "Couldn't start '{}'!: {}",
-            &exe.display(),
-            e
-        ))),
     }
 }
 
-/// Launches the game using the given auth response.
-pub fn launch_game(
-    auth_response: AuthResponse,
-    game_status: GameStatus,
-    args: &LaunchArgs,
-) -> Result<(), Error> {
-    // Path to client folder within the installation
-    let path = &args.install_path.clone().unwrap().join("client");
-    let exe = &path.clone().join("SSOClient.exe");
-    if !Path::new(exe).exists() {
-        return Err(Error::msg(
-            "No 'SSOClient.exe' is present. Make sure that this path is correct! Use --help for more info.",
-        ));
+/// Builder for launching the game from an install folder.
+///
+/// ```no_run
+/// # use sso_launcher_rs::{api::{AuthResponse, GameStatus}, GameLauncher, StarStableApi};
+/// # fn launch(api: &StarStableApi, auth: &AuthResponse, status: &GameStatus) -> Result<(), sso_launcher_rs::Error> {
+/// GameLauncher::new("C:/Program Files/Star Stable Online")
+///     .language("sv")
+///     .debug(true)
+///     .launch(api, auth, status)
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct GameLauncher {
+    /// The Star Stable Online base installation folder, SSOClient should be in a subfolder called client
+    install_path: PathBuf,
+
+    /// The language the game will be set to
+    language: String,
+
+    /// If we should log internal executable message to stdout
+    debug: bool,
+
+    /// Game arguments sent directly to the game executable, without the leading `-`
+    game_arguments: Vec<String>,
+
+    /// Folder of the ngfx.exe (Nvidia NSight EXE) to launch the game through
+    ngfx_launch_path: Option<PathBuf>,
+}
+
+impl GameLauncher {
+    pub fn new(install_path: impl Into<PathBuf>) -> Self {
+        GameLauncher {
+            install_path: install_path.into(),
+            language: "en".to_string(),
+            debug: false,
+            game_arguments: vec![],
+            ngfx_launch_path: None,
+        }
+    }
+
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.language = language.into();
+        self
     }
 
-    // Do some sanity checks before trying to launch game
-    if game_status.update_in_progress == true {
-        return Err(Error::msg(format!(
-            "Game server '{}' undergoing update to version '{}', please try again later",
-            game_status.friendly_name, game_status.game_version
-        )));
+    pub fn debug(mut self, debug: bool) -> Self {
+        self.debug = debug;
+        self
     }
 
-    if game_status.online != true && game_status.update_in_progress != true {
-        return Err(Error::msg(format!("Game server '{}' is not available at the time for unknown reason, please try again later. For more information see Star Stable Onlines's website", game_status.friendly_name)));
+    pub fn game_arguments(mut self, game_arguments: Vec<String>) -> Self {
+        self.game_arguments = game_arguments;
+        self
     }
 
-    let mut manifest = get_local_manifest(&args.install_path.clone().unwrap()).unwrap();
-    let local_gameversion = manifest["client"].take()["version"].take().to_string();
-    if game_status.game_version != local_gameversion {
-        return Err(Error::msg(format!(
-            "Game server '{}' is not the same version '{}' as installed version '{}', cannot join!",
-            game_status.friendly_name, game_status.game_version, local_gameversion
-        )));
+    pub fn ngfx_launch_path(mut self, ngfx_launch_path: impl Into<PathBuf>) -> Self {
+        self.ngfx_launch_path = Some(ngfx_launch_path.into());
+        self
     }
 
-    // Sanity checks passed, build argument structure being passed to game executable
-    let mut launch_args: Vec<String> = vec![];
+    /// Path to client folder within the installation
+    fn client_path(&self) -> PathBuf {
+        self.install_path.join("client")
+    }
 
-    match args.language.to_owned() {
-        None => {
-            launch_args.push("-Language=en".to_string());
+    /// Checks that the game is installed and the server can be joined with the installed version.
+    /// ## Returns
+    /// Result <(), Error>
+    pub fn check(&self, game_status: &GameStatus) -> Result<(), Error> {
+        if !self.client_path().join("SSOClient.exe").exists() {
+            return Err(Error::InstallMissing(
+                "No 'SSOClient.exe' is present. Make sure that this path is correct! Use --help for more info.".to_string(),
+            ));
         }
-        Some(lang) => {
-            launch_args.push(format!("-Language={:?}", lang));
+
+        // Do some sanity checks before trying to launch game
+        if game_status.update_in_progress {
+            return Err(Error::ServerUpdating {
+                server: game_status.friendly_name.clone(),
+                version: game_status.game_version.clone(),
+            });
         }
-    }
 
-    launch_args.push(format!("-NetworkUserId={}", auth_response.user_id));
-    launch_args.push(format!("-MetricsServer={}", endpoints::METRICS));
-    launch_args.push(format!("-MetricsGroup={}", "[1]"));
-    launch_args.push(format!("-LoginQueueToken={}", auth_response.queue_token));
-    launch_args.push(format!(
-        "-NetworkLauncherHash={}",
-        auth_response.launcher_hash
-    ));
-    launch_args.push(format!(
-        "-ProjectUserDataPath={}",
-        &path.clone().to_string_lossy()
-    ));
-    launch_args.push(format!(
-        "-NetworkLauncherServer={}",
-        endpoints::LAUNCHER_PROXY
-    ));
-
-    match args.game_arguments.clone() {
-        None => (),
-        Some(game_args) => {
-            for game_arg in game_args {
-                launch_args.push(format!("-{}", game_arg));
+        if !game_status.online && !game_status.update_in_progress {
+            return Err(Error::ServerOffline {
+                server: game_status.friendly_name.clone(),
+            });
+        }
+
+        let local_gameversion = match get_local_manifest(&self.install_path)? {
+            Some(manifest) => manifest.client.version,
+            None => {
+                return Err(Error::InstallMissing(
+                    "No 'manifest.json' is present, install the game with download-game first!"
+                        .to_string(),
+                ))
             }
+        };
+        if game_status.game_version != local_gameversion {
+            return Err(Error::VersionMismatch {
+                server: game_status.friendly_name.clone(),
+                server_version: game_status.game_version.clone(),
+                installed_version: local_gameversion,
+            });
         }
+
+        Ok(())
     }
-    let debug = args.debug.to_owned();
-
-    match args.ngfx_launch_path.to_owned() {
-        None => {
-            println!("Launching normal instance of game...");
-            println!(
-                "Launching game with following arguments: {}",
-                &launch_args.join(" ")
-            );
-            _launch_game(&exe, &launch_args, &path, debug)
+
+    /// Builds the argument list passed to the game executable for the given login.
+    pub fn arguments(&self, endpoints: &Endpoints, auth_response: &AuthResponse) -> Vec<String> {
+        let mut launch_args: Vec<String> = vec![];
+
+        launch_args.push(format!("-Language={}", self.language));
+        launch_args.push(format!("-NetworkUserId={}", auth_response.user_id));
+        launch_args.push(format!("-MetricsServer={}", endpoints.metrics));
+        launch_args.push(format!("-MetricsGroup={}", "[1]"));
+        launch_args.push(format!("-LoginQueueToken={}", auth_response.queue_token));
+        launch_args.push(format!(
+            "-NetworkLauncherHash={}",
+            auth_response.launcher_hash
+        ));
+        launch_args.push(format!(
+            "-ProjectUserDataPath={}",
+            self.client_path().to_string_lossy()
+        ));
+        launch_args.push(format!(
+            "-NetworkLauncherServer={}",
+            endpoints.launcher_proxy
+        ));
+
+        for game_arg in &self.game_arguments {
+            launch_args.push(format!("-{}", game_arg));
         }
+        launch_args
+    }
+
+    /// Launches the game using the given auth response, after the sanity checks of `check`.
+    /// ## Returns
+    /// Result <(), Error>
+    pub fn launch(
+        &self,
+        api: &StarStableApi,
+        auth_response: &AuthResponse,
+        game_status: &GameStatus,
+    ) -> Result<(), Error> {
+        self.check(game_status)?;
+
+        // Sanity checks passed, build argument structure being passed to game executable
+        let path = &self.client_path();
+        let exe = &path.join("SSOClient.exe");
+        let launch_args = self.arguments(api.endpoints(), auth_response);
 
-        Some(ngfx_path) => {
-            let mut ngfx_launch_args: Vec<String> = vec![];
-            ngfx_launch_args.push(format!("--activity={}", "Frame Debugger"));
-            ngfx_launch_args.push(format!("--platform={}", "Windows"));
-            ngfx_launch_args.push(format!("--dir=\"{}\"", &path.display()));
-            ngfx_launch_args.push(format!("--exe=\"{}\"", &exe.display()));
-            ngfx_launch_args.push(format!("--args={}", &launch_args.join(" ")));
-            ngfx_launch_args.push(format!("{}", "--verbose"));
-            ngfx_launch_args.push(format!("{}", "--launch-detached"));
-
-            let ngfx_exe = &ngfx_path.join("ngfx.exe");
-            println!("Launching NGFX instance of game...");
-            println!(
-                "Launching game with following arguments: {}",
-                ngfx_launch_args.clone().join(" ")
-            );
-            _launch_game(&ngfx_exe, &ngfx_launch_args, &path, debug)
+        match &self.ngfx_launch_path {
+            None => {
+                api.log("Launching normal instance of game...");
+                api.log(&format!(
+                    "Launching game with following arguments: {}",
+                    &launch_args.join(" ")
+                ));
+                _launch_game(api, exe, &launch_args, path, self.debug)
+            }
+
+            Some(ngfx_path) => {
+                let mut ngfx_launch_args: Vec<String> = vec![];
+                ngfx_launch_args.push(format!("--activity={}", "Frame Debugger"));
+                ngfx_launch_args.push(format!("--platform={}", "Windows"));
+                ngfx_launch_args.push(format!("--dir=\"{}\"", &path.display()));
+                ngfx_launch_args.push(format!("--exe=\"{}\"", &exe.display()));
+                ngfx_launch_args.push(format!("--args={}", &launch_args.join(" ")));
+                ngfx_launch_args.push("--verbose".to_string());
+                ngfx_launch_args.push("--launch-detached".to_string());
+
+                let ngfx_exe = &ngfx_path.join("ngfx.exe");
+                api.log("Launching NGFX instance of game...");
+                api.log(&format!(
+                    "Launching game with following arguments: {}",
+                    ngfx_launch_args.clone().join(" ")
+                ));
+                _launch_game(api, ngfx_exe, &ngfx_launch_args, path, self.debug)
+            }
         }
     }
 }
@@ -0,0 +1,42 @@
+//! Less bloat Star Stable Online launcher.
+//!
+//! The library talks to the Star Stable back-ends, manages game installs and launches the game
+//! without any CLI concerns, the `sso-launcher-rs` binary is a thin wrapper around it. Nothing
+//! is printed: progress messages go to the logger set with `with_logger`, file transfers report
+//! `download::DownloadProgress`, and install states and verify reports are returned.
+//!
+//! ```no_run
+//! use sso_launcher_rs::{Config, GameLauncher, StarStableApi};
+//!
+//! let config = Config::load(None)?;
+//! let api = StarStableApi::new(config.endpoints, &config.http)?;
+//! let (auth, status) = api.login_with_status("email".into(), "password".into(), None)?;
+//! GameLauncher::new("C:/Program Files/Star Stable Online").launch(&api, &auth, &status)?;
+//! # Ok::<(), sso_launcher_rs::Error>(())
+//! ```
+pub mod api;
+pub mod async_api;
+pub mod config;
+pub mod download;
+pub mod endpoints;
+pub mod error;
+pub mod launch;
+pub mod password;
+pub mod session;
+pub mod status;
+mod stream;
+mod utils;
+pub mod vault;
+pub mod verify;
+
+pub use api::StarStableApi;
+pub use async_api::AsyncStarStableApi;
+pub use config::{Config, HttpOptions};
+pub use download::{DownloadGameOptions, TransferOptions};
+pub use endpoints::{EndpointOverrides, Endpoints};
+pub use error::Error;
+pub use launch::GameLauncher;
+pub use password::PasswordSource;
+pub use session::SessionCache;
+pub use vault::Vault;
+pub use verify::VerifyOptions;
@@ -1,29 +1,97 @@
-mod api;
-mod download;
-mod endpoints;
-mod launch;
-mod status;
-mod utils;
-
-use crate::api::StarStableApi;
-use crate::download::{download_game, download_launcher};
-use crate::launch::launch_game;
-use crate::status::status_game;
-use clap::{Args, Parser, Subcommand, ValueEnum};
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
 use colored::Colorize;
-use std::path::PathBuf;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use sso_launcher_rs::download::{DownloadProgress, InstallType, ProgressHandler};
+use sso_launcher_rs::status::{render, StatusFormat};
+use sso_launcher_rs::verify::VerifyReport;
+use sso_launcher_rs::api::Challenge;
+use sso_launcher_rs::error::LoginFailure;
+use sso_launcher_rs::password::{command_output, PASSWORD_ENV};
+use sso_launcher_rs::vault::Account;
+use sso_launcher_rs::{
+    Config, DownloadGameOptions, EndpointOverrides, Error, GameLauncher, PasswordSource, StarStableApi,
+    SessionCache, TransferOptions, Vault, VerifyOptions,
+};
+use std::io::{self, BufRead, ErrorKind as IoErrorKind, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 use std::process::exit;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 struct Cli {
-    /// The email used to log in
+    /// The email used to log in, only needed by commands talking to the game server
     #[arg(short = 'e', long)]
-    email: String,
+    email: Option<String>,
 
-    /// The password used to log in
-    #[arg(short = 'p', long)]
-    password: String,
+    /// The password used to log in, visible in shell history and process listings, prefer one
+    /// of the other password sources. Ignored when SSO_PASSWORD is set, without any source the
+    /// password is prompted for
+    #[arg(short = 'p', long, group = "password_source")]
+    password: Option<String>,
+
+    /// Read the password from the first line of stdin
+    #[arg(long, group = "password_source")]
+    password_stdin: bool,
+
+    /// Read the password from the first line of a file
+    #[arg(long, group = "password_source")]
+    password_file: Option<PathBuf>,
+
+    /// Run a command and use the first line of its output as password, e.g. "pass show sso"
+    #[arg(long, group = "password_source")]
+    password_command: Option<String>,
+
+    /// Log in with an account stored with 'accounts add' instead of email and password. An
+    /// account from SSO_ACCOUNT is ignored when --email is given
+    #[arg(long, env = "SSO_ACCOUNT")]
+    account: Option<String>,
+
+    /// Whether `account` came from SSO_ACCOUNT rather than the command line
+    #[arg(skip)]
+    account_from_env: bool,
+
+    /// Verification code for logins that ask for one, e.g. mailed to you or from an authenticator app
+    #[arg(long, env = "SSO_VERIFICATION_CODE", group = "code_source")]
+    code: Option<String>,
+
+    /// Run a command and use the first line of its output as verification code, e.g. "oathtool --totp KEY"
+    #[arg(long, group = "code_source")]
+    code_command: Option<String>,
+
+    /// Always log in, instead of reusing the cached session of the account
+    #[arg(long)]
+    no_session_cache: bool,
+
+    /// Launcher version sent on login, defaults to the version of the latest official launcher
+    #[arg(long, env = "SSO_LAUNCHER_VERSION")]
+    launcher_version: Option<String>,
+
+    /// Path to the config file, defaults to config.toml in the user config folder
+    #[arg(long, env = "SSO_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// Base URL of the launcher proxy, login, queue and game server URLs follow it unless configured
+    #[arg(long)]
+    launcher_proxy_url: Option<String>,
+
+    /// Base URL the game files are downloaded from
+    #[arg(long)]
+    game_files_url: Option<String>,
+
+    /// Base URL the official launcher and its latest.yml are downloaded from
+    #[arg(long)]
+    launcher_files_url: Option<String>,
+
+    /// Seconds to wait for a connection to the servers, overrides the config
+    #[arg(long)]
+    connect_timeout: Option<u64>,
+
+    /// Seconds an API request may take, and a download may go without data, overrides the config
+    #[arg(long)]
+    read_timeout: Option<u64>,
 
     #[command(subcommand)]
     command: Commands,
@@ -34,14 +102,42 @@ enum Commands {
     /// Launches the game
     Launch(LaunchArgs),
 
-    /// Downloads the game, use this if you fail to join a server, if files already exist, it will overwrite the files at the given directory
+    /// Downloads or updates the game, only changed files are downloaded and files no longer part of the game are removed
     DownloadGame(DownloadGameArgs),
 
     /// Fetches Server status for the logged in account
-    Status,
+    Status(StatusArgs),
 
     /// Downloads the official launcher directly to path
     DownloadLauncher(DownloadLauncherArgs),
+
+    /// Hashes every installed game file and reports missing, modified and extra files
+    Verify(VerifyArgs),
+
+    /// Verifies the installed game files and re-downloads only the missing and modified ones
+    Repair(VerifyArgs),
+
+    /// Manages accounts stored in the encrypted vault, unlocked with SSO_VAULT_PASSPHRASE or a prompt
+    #[command(subcommand)]
+    Accounts(AccountsCommands),
+}
+
+#[derive(Subcommand)]
+enum AccountsCommands {
+    /// Stores the account given with --email and a password source under an alias
+    Add {
+        /// Name to log in with using --account
+        alias: String,
+    },
+
+    /// Lists the stored aliases and their emails
+    List,
+
+    /// Removes a stored account
+    Remove {
+        /// Alias of the account
+        alias: String,
+    },
 }
 
 #[derive(Args)]
@@ -59,7 +155,7 @@ struct LaunchArgs {
         long,
         default_value = "C:/Program Files/Star Stable Online"
     )]
-    install_path: Option<PathBuf>,
+    install_path: PathBuf,
 
     /// If we should log internal executable message to stdout
     #[arg(long)]
@@ -88,7 +184,7 @@ struct LaunchArgs {
 
 #[derive(Args, Debug)]
 struct DownloadGameArgs {
-    /// Version override
+    /// Version override, logging in to look up the server version is skipped when given
     #[arg(short = 'v', long)]
     version: Option<String>,
 
@@ -98,7 +194,133 @@ struct DownloadGameArgs {
         long,
         default_value = "C:/Program Files/Star Stable Online"
     )]
-    install_path: Option<PathBuf>,
+    install_path: PathBuf,
+
+    /// Only report the install state and pending changes, without downloading anything
+    #[arg(long)]
+    check: bool,
+
+    #[command(flatten)]
+    transfer: TransferArgs,
+}
+
+#[derive(Args, Debug)]
+struct TransferArgs {
+    /// How many game files are downloaded at the same time
+    #[arg(short = 'j', long, default_value_t = 8)]
+    concurrency: usize,
+
+    /// How many times a failed file download is retried before giving up
+    #[arg(long, default_value_t = 3)]
+    retries: u32,
+}
+
+#[derive(Args, Debug)]
+struct StatusArgs {
+    /// How to print the status, waybar and i3blocks print a status bar block
+    #[arg(short = 'f', long, default_value = "plain", value_enum)]
+    format: Format,
+}
+
+#[derive(Args, Debug)]
+struct VerifyArgs {
+    /// Version override, defaults to the installed version
+    #[arg(short = 'v', long)]
+    version: Option<String>,
+
+    /// The path to the Star Stable Online base installation folder
+    #[arg(
+        short = 'i',
+        long,
+        default_value = "C:/Program Files/Star Stable Online"
+    )]
+    install_path: PathBuf,
+
+    #[command(flatten)]
+    transfer: TransferArgs,
+}
+
+impl Cli {
+    /// Whether a password source was given on the command line.
+    fn has_password_source(&self) -> bool {
+        self.password.is_some()
+            || self.password_stdin
+            || self.password_file.is_some()
+            || self.password_command.is_some()
+    }
+
+    /// Alias of the vault account to log in with. `accounts add` stores the account given with
+    /// `--email`, and an explicit `--email` wins over an exported SSO_ACCOUNT.
+    fn account(&self) -> Option<&str> {
+        match self.command {
+            Commands::Accounts(AccountsCommands::Add { .. }) => None,
+            _ if self.account_from_env && self.email.is_some() => None,
+            _ => self.account.as_deref(),
+        }
+    }
+}
+
+impl LaunchArgs {
+    fn launcher(&self) -> GameLauncher {
+        let mut launcher = GameLauncher::new(&self.install_path)
+            .language(format!("{:?}", self.language.clone().unwrap_or(Language::en)))
+            .debug(self.debug)
+            .game_arguments(self.game_arguments.clone().unwrap_or_default());
+        if let Some(ngfx_launch_path) = &self.ngfx_launch_path {
+            launcher = launcher.ngfx_launch_path(ngfx_launch_path);
+        }
+        launcher
+    }
+}
+
+impl DownloadGameArgs {
+    fn options(&self, version: String) -> DownloadGameOptions {
+        DownloadGameOptions {
+            version,
+            install_path: self.install_path.clone(),
+            transfer: self.transfer.options(),
+        }
+    }
+}
+
+impl TransferArgs {
+    fn options(&self) -> TransferOptions {
+        TransferOptions {
+            concurrency: self.concurrency,
+            retries: self.retries,
+        }
+    }
+}
+
+impl StatusArgs {
+    fn format(&self) -> StatusFormat {
+        match self.format {
+            Format::Plain => StatusFormat::Plain,
+            Format::Table => StatusFormat::Table,
+            Format::Json => StatusFormat::Json,
+            Format::Waybar => StatusFormat::Waybar,
+            Format::I3blocks => StatusFormat::I3blocks,
+        }
+    }
+}
+
+impl VerifyArgs {
+    fn options(&self) -> VerifyOptions {
+        VerifyOptions {
+            version: self.version.clone(),
+            install_path: self.install_path.clone(),
+            transfer: self.transfer.options(),
+        }
+    }
+}
+
+#[derive(Clone, ValueEnum, Debug)]
+enum Format {
+    Plain,
+    Table,
+    Json,
+    Waybar,
+    I3blocks,
 }
 
 #[derive(Clone, ValueEnum, Debug)]
@@ -121,36 +343,383 @@ enum Language {
 }
 
 fn main() {
-    let cli = Cli::parse();
-    let auth_response =
-        StarStableApi::login(cli.email.to_owned(), cli.password.to_owned()).unwrap();
-    let game_status =
-        StarStableApi::get_game_server_data(auth_response.launcher_hash.clone()).unwrap();
+    let matches = Cli::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    cli.account_from_env = matches.value_source("account") == Some(ValueSource::EnvVariable);
+
+    // Only an --account given on the command line clashes with the other credentials, an
+    // exported SSO_ACCOUNT gives way to them
+    if cli.account.is_some()
+        && !cli.account_from_env
+        && (cli.email.is_some() || cli.has_password_source())
+    {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--account can't be combined with --email or a password source",
+            )
+            .exit();
+    }
+
+    if let Err(e) = run(&cli) {
+        eprintln!("{}: {}", "error".bright_red().bold(), e);
+        exit(e.exit_code());
+    }
+}
+
+/// Runs the given command, logging in only for commands that need the game server.
+/// See `Error` for the exit code of each failure.
+fn run(cli: &Cli) -> Result<(), Error> {
+    let mut config = Config::load(cli.config.as_deref())?;
+    if let Some(connect_timeout) = cli.connect_timeout {
+        config.http.connect_timeout = connect_timeout;
+    }
+    if let Some(read_timeout) = cli.read_timeout {
+        config.http.read_timeout = read_timeout;
+    }
+    config.override_endpoints(&EndpointOverrides {
+        launcher_proxy: cli.launcher_proxy_url.clone(),
+        game_files: cli.game_files_url.clone(),
+        launcher_files: cli.launcher_files_url.clone(),
+        ..EndpointOverrides::default()
+    });
+
+    let launcher_version = cli.launcher_version.as_deref().or(config.launcher_version.as_deref());
+    let api = &StarStableApi::new(config.endpoints.clone(), &config.http)?
+        .with_challenge_handler(challenge_handler(cli))
+        .with_logger(|message| eprintln!("{}", message));
+
+    // The vault is only unlocked when a login is actually needed, not to resume a session
+    let login = || match session_cache(cli) {
+        Some(cache) => cache.login(api, || credentials(cli), launcher_version),
+        None => {
+            let (email, password) = credentials(cli)?;
+            api.login_with_status(email, password, launcher_version)
+        }
+    };
+
+    // Skips the login queue, which only matters for launching the game
+    let game_status = || match session_cache(cli) {
+        Some(cache) => cache.status(api, || credentials(cli), launcher_version),
+        None => {
+            let (email, password) = credentials(cli)?;
+            api.login_status_only(email, password, launcher_version)
+        }
+    };
 
     match &cli.command {
         Commands::Launch(args) => {
-            if let Err(e) = launch_game(auth_response, game_status, args) {
-                eprintln!("{}: {}", "error".bright_red().bold(), e);
-                exit(1);
-            }
+            let (auth_response, game_status) = login()?;
+            args.launcher().launch(api, &auth_response, &game_status)
         }
         Commands::DownloadGame(args) => {
-            if let Err(e) = download_game(auth_response, game_status, args) {
-                eprintln!("{}: {}", "error".bright_red().bold(), e);
-                exit(1);
-            }
+            let version = match &args.version {
+                Some(version) => version.to_owned(),
+                None => game_status()?.game_version,
+            };
+            download_game(api, args, version)
         }
-        Commands::Status => {
-            if let Err(e) = status_game(game_status) {
-                eprintln!("{}: {}", "error".bright_red().bold(), e);
-                exit(1);
-            }
+        Commands::Status(args) => {
+            println!("{}", render(&game_status()?, args.format())?);
+            Ok(())
         }
         Commands::DownloadLauncher(args) => {
-            if let Err(e) = download_launcher(args) {
-                eprintln!("{}: {}", "error".bright_red().bold(), e);
-                exit(1);
+            api.download_launcher(args.download_path.clone())?;
+            Ok(())
+        }
+        Commands::Verify(args) => verify(api, args),
+        Commands::Repair(args) => repair(api, args),
+        Commands::Accounts(command) => accounts(cli, command),
+    }
+}
+
+/// Prints the install state of `args.install_path` and brings it to `version`, unless only
+/// `--check` is asked for.
+fn download_game(api: &StarStableApi, args: &DownloadGameArgs, version: String) -> Result<(), Error> {
+    let options = args.options(version);
+    let plan = api.check_game(&options)?;
+    println!(
+        "Install state: {} ({} added, {} changed, {} removed)",
+        plan.install_type,
+        plan.diff.added.len(),
+        plan.diff.changed.len(),
+        plan.diff.removed.len()
+    );
+
+    if args.check {
+        return Ok(());
+    }
+    with_progress_bar(|on_progress| api.install_game(&options, &plan, on_progress))
+}
+
+/// Prints the verify report of the install, failing with `Error::Integrity` if it is damaged.
+fn verify(api: &StarStableApi, args: &VerifyArgs) -> Result<(), Error> {
+    let report = api.verify_game(&args.options())?;
+    print_report(&report);
+
+    if !report.is_intact() {
+        return Err(Error::Integrity(format!(
+            "Install at '{}' is damaged, run 'repair' to re-download {} files",
+            args.install_path.display(),
+            report.missing.len() + report.modified.len()
+        )));
+    }
+
+    println!("All {} game files are intact", report.files.len());
+    Ok(())
+}
+
+/// Prints the verify report of the install and re-downloads the damaged files.
+fn repair(api: &StarStableApi, args: &VerifyArgs) -> Result<(), Error> {
+    let options = args.options();
+    let report = api.verify_game(&options)?;
+    print_report(&report);
+
+    if report.is_intact() {
+        println!("Install state: {}", InstallType::Ready);
+        return Ok(());
+    }
+
+    println!("Install state: {}", InstallType::Repair);
+    with_progress_bar(|on_progress| api.repair_game(&options, &report, on_progress))
+}
+
+fn print_report(report: &VerifyReport) {
+    for file in &report.missing {
+        println!("{}: {}", "missing".bright_red().bold(), file.path);
+    }
+
+    for file in &report.modified {
+        println!("{}: {}", "modified".bright_yellow().bold(), file.path);
+    }
+
+    for path in &report.extra {
+        println!("{}: {}", "extra".bright_blue().bold(), path);
+    }
+
+    println!(
+        "{} missing, {} modified, {} extra files",
+        report.missing.len(),
+        report.modified.len(),
+        report.extra.len()
+    );
+}
+
+/// Runs `transfer` with a progress bar on stderr, which shows up once the first file download
+/// starts and is left as it is when the transfer fails.
+fn with_progress_bar(
+    transfer: impl FnOnce(&ProgressHandler<'_>) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let progress = ProgressBar::hidden();
+    let total_files = AtomicUsize::new(0);
+    let on_progress = |event: DownloadProgress| match event {
+        DownloadProgress::Started { files, bytes, workers } => {
+            eprintln!("Downloading {} files using {} workers...", files, workers);
+            total_files.store(files, Ordering::SeqCst);
+            progress.set_length(bytes);
+            progress.set_style(
+                ProgressStyle::with_template(
+                    "[{elapsed_precise}] [{bar:40}] {bytes}/{total_bytes} ({bytes_per_sec}, ETA {eta}) {msg}",
+                )
+                .expect("Invalid progress bar template!")
+                .progress_chars("=> "),
+            );
+            progress.set_message(format!("0/{} files", files));
+            progress.set_draw_target(ProgressDrawTarget::stderr());
+        }
+        DownloadProgress::Advanced { bytes } => progress.inc(bytes),
+        DownloadProgress::FileFinished { finished, .. } => progress.set_message(format!(
+            "{}/{} files",
+            finished,
+            total_files.load(Ordering::SeqCst)
+        )),
+        DownloadProgress::Retrying {
+            path,
+            attempt,
+            retries,
+            error,
+        } => progress.println(format!(
+            "Retrying '{}' ({}/{}): {}",
+            path, attempt, retries, error
+        )),
+    };
+
+    match transfer(&on_progress) {
+        Ok(_) => {
+            progress.finish();
+            Ok(())
+        }
+        Err(e) => {
+            progress.abandon();
+            Err(e)
+        }
+    }
+}
+
+/// Environment variable the vault passphrase is read from before prompting.
+const VAULT_PASSPHRASE_ENV: &str = "SSO_VAULT_PASSPHRASE";
+
+/// Runs an `accounts` subcommand against the vault in the user config folder.
+fn accounts(cli: &Cli, command: &AccountsCommands) -> Result<(), Error> {
+    let path = &vault_path()?;
+    match command {
+        AccountsCommands::Add { alias } => {
+            let (email, password) = credentials(cli)?;
+            let passphrase = vault_passphrase(!path.exists())?;
+            let mut vault = Vault::open(path, &passphrase)?;
+            vault.add(alias.to_owned(), Account { email, password });
+            vault.save(path, &passphrase)?;
+            println!("Stored account '{}'", alias);
+        }
+        AccountsCommands::List => {
+            let vault = Vault::open(path, &vault_passphrase(false)?)?;
+            for (alias, account) in vault.accounts() {
+                println!("{}: {}", alias.bold(), account.email);
+            }
+        }
+        AccountsCommands::Remove { alias } => {
+            let passphrase = vault_passphrase(false)?;
+            let mut vault = Vault::open(path, &passphrase)?;
+            if vault.remove(alias).is_none() {
+                return Err(Error::Credentials(format!("No account named '{}'", alias)));
             }
+            vault.save(path, &passphrase)?;
+            println!("Removed account '{}'", alias);
+        }
+    }
+    Ok(())
+}
+
+fn vault_path() -> Result<PathBuf, Error> {
+    Vault::default_path().ok_or_else(|| {
+        Error::Io(io::Error::new(
+            IoErrorKind::NotFound,
+            "Couldn't find the user config folder for the account vault",
+        ))
+    })
+}
+
+/// The vault passphrase from `SSO_VAULT_PASSPHRASE`, or a prompt. `confirm` asks twice, for
+/// creating a new vault.
+fn vault_passphrase(confirm: bool) -> Result<String, Error> {
+    if let Ok(passphrase) = std::env::var(VAULT_PASSPHRASE_ENV) {
+        if !passphrase.is_empty() {
+            return Ok(passphrase);
+        }
+    }
+
+    if !io::stdin().is_terminal() {
+        return Err(Error::Credentials(format!(
+            "No terminal to prompt for the vault passphrase on, set {}",
+            VAULT_PASSPHRASE_ENV
+        )));
+    }
+
+    let passphrase = rpassword::prompt_password("Vault passphrase: ")?;
+    if passphrase.is_empty() {
+        return Err(Error::Credentials("Empty vault passphrase".to_string()));
+    }
+    if confirm && rpassword::prompt_password("Repeat vault passphrase: ")? != passphrase {
+        return Err(Error::Credentials("Vault passphrases don't match".to_string()));
+    }
+    Ok(passphrase)
+}
+
+/// The account stored under `alias` in the vault at `path`.
+fn vault_account(path: &Path, alias: &str) -> Result<Account, Error> {
+    let vault = Vault::open(path, &vault_passphrase(false)?)?;
+    match vault.get(alias) {
+        Some(account) => Ok(account.clone()),
+        None => Err(Error::Credentials(format!(
+            "No account named '{}', add it with 'accounts add {}'",
+            alias, alias
+        ))),
+    }
+}
+
+/// The login credentials from the vault account given with `--account`, or from `--email` and
+/// the password source. Exits with a usage error if neither an account nor an email is given.
+fn credentials(cli: &Cli) -> Result<(String, String), Error> {
+    match cli.account() {
+        Some(alias) => {
+            let account = vault_account(&vault_path()?, alias)?;
+            Ok((account.email, account.password))
         }
+        None => Ok((email(cli), password_source(cli).read()?)),
+    }
+}
+
+/// The email given with `--email`, exits with a usage error without one.
+fn email(cli: &Cli) -> String {
+    match &cli.email {
+        Some(email) => email.to_owned(),
+        None => Cli::command()
+            .error(
+                ErrorKind::MissingRequiredArgument,
+                "--email or --account is required to log in for this command",
+            )
+            .exit(),
+    }
+}
+
+/// Answers verification challenges with `--code`, the output of `--code-command`, or a prompt.
+fn challenge_handler(cli: &Cli) -> impl Fn(&Challenge) -> Result<String, Error> {
+    let code = cli.code.clone();
+    let code_command = cli.code_command.clone();
+    move |challenge| {
+        let code = if let Some(code) = &code {
+            code.to_owned()
+        } else if let Some(command) = &code_command {
+            command_output(command)?
+        } else if io::stdin().is_terminal() {
+            print!("{}: ", challenge);
+            io::stdout().flush()?;
+            let mut line = String::new();
+            io::stdin().lock().read_line(&mut line)?;
+            line
+        } else {
+            return Err(Error::Login {
+                reason: LoginFailure::VerificationRequired,
+                message: challenge.message.clone(),
+            });
+        };
+
+        Ok(code.lines().next().unwrap_or_default().trim().to_owned())
+    }
+}
+
+/// Session cache of the account logging in, keyed by the alias with `--account` so resuming a
+/// session doesn't unlock the vault. `None` with `--no-session-cache`.
+fn session_cache(cli: &Cli) -> Option<SessionCache> {
+    if cli.no_session_cache {
+        return None;
+    }
+
+    match cli.account() {
+        Some(alias) => SessionCache::for_account(alias),
+        None => SessionCache::for_email(&email(cli)),
+    }
+}
+
+/// Picks the password source: the explicit flags first, then `SSO_PASSWORD`, then `--password`
+/// as the last resort before a prompt, since argv leaks into shell history and process listings.
+fn password_source(cli: &Cli) -> PasswordSource {
+    if cli.password_stdin {
+        PasswordSource::Stdin
+    } else if let Some(path) = &cli.password_file {
+        PasswordSource::File(path.to_owned())
+    } else if let Some(command) = &cli.password_command {
+        PasswordSource::Command(command.to_owned())
+    } else if std::env::var_os(PASSWORD_ENV).is_some() {
+        PasswordSource::Env
+    } else if let Some(password) = &cli.password {
+        eprintln!(
+            "{}: --password is visible in shell history and process listings, prefer --password-stdin, --password-file, --password-command or {}",
+            "warning".bright_yellow().bold(),
+            PASSWORD_ENV
+        );
+        PasswordSource::Argument(password.to_owned())
+    } else {
+        PasswordSource::Prompt
     }
 }
@@ -0,0 +1,122 @@
+use crate::error::Error;
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Environment variable the password is read from when no other source is given.
+pub const PASSWORD_ENV: &str = "SSO_PASSWORD";
+
+/// Where the login password comes from.
+#[derive(Debug, Clone)]
+pub enum PasswordSource {
+    /// Given on the command line, visible in shell history and process listings
+    Argument(String),
+
+    /// First line of standard input
+    Stdin,
+
+    /// First line of a file
+    File(PathBuf),
+
+    /// First line of the output of a shell command, e.g. `pass show sso`
+    Command(String),
+
+    /// The `SSO_PASSWORD` environment variable
+    Env,
+
+    /// Hidden interactive prompt on the terminal
+    Prompt,
+}
+
+impl PasswordSource {
+    /// Reads the password from this source.
+    /// ## Returns
+    /// The password without its trailing newline, or `Error::Credentials` if the source is
+    /// unavailable or empty.
+    pub fn read(&self) -> Result<String, Error> {
+        let password = match self {
+            PasswordSource::Argument(password) => password.to_owned(),
+            PasswordSource::Stdin => {
+                let mut line = String::new();
+                io::stdin().lock().read_line(&mut line)?;
+                line
+            }
+            PasswordSource::File(path) => fs::read_to_string(path).map_err(|e| {
+                Error::Credentials(format!(
+                    "Couldn't read password file '{}': {}",
+                    path.display(),
+                    e
+                ))
+            })?,
+            PasswordSource::Command(command) => command_output(command)?,
+            PasswordSource::Env => env::var(PASSWORD_ENV).map_err(|_| {
+                Error::Credentials(format!("{} is not set", PASSWORD_ENV))
+            })?,
+            PasswordSource::Prompt => {
+                if !io::stdin().is_terminal() {
+                    return Err(Error::Credentials(
+                        "No password given and no terminal to prompt on, use --password-stdin, --password-file, --password-command or SSO_PASSWORD".to_string(),
+                    ));
+                }
+                rpassword::prompt_password("Password: ")?
+            }
+        };
+
+        let password = password.lines().next().unwrap_or_default().to_owned();
+        if password.is_empty() {
+            return Err(Error::Credentials(format!(
+                "Empty password read from {}",
+                self
+            )));
+        }
+        Ok(password)
+    }
+}
+
+impl std::fmt::Display for PasswordSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PasswordSource::Argument(_) => write!(f, "--password"),
+            PasswordSource::Stdin => write!(f, "standard input"),
+            PasswordSource::File(path) => write!(f, "'{}'", path.display()),
+            PasswordSource::Command(command) => write!(f, "'{}'", command),
+            PasswordSource::Env => write!(f, "{}", PASSWORD_ENV),
+            PasswordSource::Prompt => write!(f, "prompt"),
+        }
+    }
+}
+
+/// Runs `command` through the platform shell, its stderr and stdin are passed through so
+/// password managers can ask for their own passphrase.
+/// ## Returns
+/// Everything the command printed, or `Error::Credentials` if it couldn't run or failed.
+pub fn command_output(command: &str) -> Result<String, Error> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+
+    let output = shell
+        .arg(command)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|e| Error::Credentials(format!("Couldn't run '{}': {}", command, e)))?;
+    if !output.status.success() {
+        return Err(Error::Credentials(format!(
+            "Command '{}' failed with {}",
+            command, output.status
+        )));
+    }
+
+    String::from_utf8(output.stdout).map_err(|_| {
+        Error::Credentials(format!("Command '{}' printed invalid UTF-8", command))
+    })
+}
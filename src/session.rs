@@ -0,0 +1,156 @@
+use crate::api::{AuthResponse, GameStatus, StarStableApi};
+use crate::error::Error;
+use crate::utils;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Login that can be resumed without sending the password again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    /// The users Account ID
+    pub user_id: String,
+
+    /// The users Launcher Hash, aka. Auth Token
+    pub launcher_hash: String,
+
+    /// When the login happened, in seconds since the unix epoch
+    pub created_at: u64,
+}
+
+/// Cached session of a single account, stored in the cache folder and readable only by the
+/// current user. Kept until the server rejects its Launcher Hash.
+#[derive(Debug, Clone)]
+pub struct SessionCache {
+    /// File the session is stored in
+    path: PathBuf,
+}
+
+impl SessionCache {
+    pub fn new(path: PathBuf) -> Self {
+        SessionCache { path }
+    }
+
+    /// Cache of the account logging in with `email`, in `sessions/` of the cache folder. The
+    /// file is named after the hash of the email so it doesn't show up in file listings.
+    pub fn for_email(email: &str) -> Option<Self> {
+        Self::for_key(&email.trim().to_lowercase())
+    }
+
+    /// Cache of the vault account stored as `alias`, so a cached session can be resumed without
+    /// unlocking the vault to look up the email.
+    pub fn for_account(alias: &str) -> Option<Self> {
+        Self::for_key(&format!("account:{}", alias))
+    }
+
+    /// Cache in `sessions/` of the cache folder, named after the hash of `key`.
+    fn for_key(key: &str) -> Option<Self> {
+        let key = hex::encode(Sha256::digest(key));
+        utils::cache_dir().map(|dir| SessionCache::new(dir.join("sessions").join(key + ".json")))
+    }
+
+    /// The cached session, a missing or unreadable cache counts as no session.
+    pub fn load(&self) -> Option<Session> {
+        let data = fs::read_to_string(&self.path).ok()?;
+        serde_json::from_str(&data).ok()
+    }
+
+    /// Caches the session of the login with `user_id` and `launcher_hash`, created now.
+    pub fn store(&self, user_id: &str, launcher_hash: &str) -> Result<(), Error> {
+        let session = Session {
+            user_id: user_id.to_owned(),
+            launcher_hash: launcher_hash.to_owned(),
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs()),
+        };
+        utils::write_private_file(&self.path, serde_json::to_string(&session)?.as_bytes())
+    }
+
+    /// Forgets the cached session.
+    pub fn clear(&self) -> Result<(), Error> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(Error::from(e)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Resumes the cached session, or logs in with the email and password from `credentials` if
+    /// there is none or the server rejects it. `credentials` is only called when a login is
+    /// needed.
+    /// ## Returns
+    /// The login, with a queue token once the login queue has been passed, and the game server
+    /// status.
+    pub fn login(
+        &self,
+        api: &StarStableApi,
+        credentials: impl FnOnce() -> Result<(String, String), Error>,
+        launcher_version: Option<&str>,
+    ) -> Result<(AuthResponse, GameStatus), Error> {
+        self.with_session(api, credentials, launcher_version, |user_id, launcher_hash| {
+            api.resume_session(user_id, launcher_hash)
+        })
+    }
+
+    /// Same as `login`, but only fetches the game server status without entering the login
+    /// queue.
+    /// ## Returns
+    /// The game server status.
+    pub fn status(
+        &self,
+        api: &StarStableApi,
+        credentials: impl FnOnce() -> Result<(String, String), Error>,
+        launcher_version: Option<&str>,
+    ) -> Result<GameStatus, Error> {
+        self.with_session(api, credentials, launcher_version, |_, launcher_hash| {
+            api.get_game_server_data(launcher_hash)
+        })
+    }
+
+    /// Runs `resume` with the cached session, logging in again and caching the new session if
+    /// there is none or `resume` fails with `Error::AuthRejected`.
+    fn with_session<T>(
+        &self,
+        api: &StarStableApi,
+        credentials: impl FnOnce() -> Result<(String, String), Error>,
+        launcher_version: Option<&str>,
+        resume: impl Fn(String, String) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        if let Some(session) = self.load() {
+            api.log(&format!("Reusing session from {}...", age(session.created_at)));
+            match resume(session.user_id, session.launcher_hash) {
+                Err(Error::AuthRejected(reason)) => {
+                    api.log(&format!(
+                        "Cached session is no longer valid ({}), logging in again",
+                        reason
+                    ));
+                    self.clear()?;
+                }
+                result => return result,
+            }
+        }
+
+        let (email, password) = credentials()?;
+        let (user_id, launcher_hash) = api.authenticate(email, password, launcher_version)?;
+        if let Err(e) = self.store(&user_id, &launcher_hash) {
+            api.log(&format!("Couldn't cache session: {}", e));
+        }
+        resume(user_id, launcher_hash)
+    }
+}
+
+/// How long ago `created_at` was, e.g. `5 minutes ago`.
+fn age(created_at: u64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let seconds = now.saturating_sub(created_at);
+    match seconds {
+        0..=59 => format!("{} seconds ago", seconds),
+        60..=3599 => format!("{} minutes ago", seconds / 60),
+        _ => format!("{} hours ago", seconds / 3600),
+    }
+}
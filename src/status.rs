@@ -1,7 +1,89 @@
-use crate::api::GameStatus;
-use anyhow::Error;
+use crate::api::{GameStatus, MessageCode, ServerState};
+use crate::error::Error;
+use serde_json::json;
 
-pub fn status_game(game_status: GameStatus) -> Result<(), Error> {
-    println!("{:?}", game_status);
-    Ok(())
+/// How `render` formats the server status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StatusFormat {
+    /// One line, e.g. `region 1 — Jorvik: Online, v2.0.0`
+    #[default]
+    Plain,
+
+    /// One aligned `key  value` row per field
+    Table,
+
+    /// A JSON object with every field, for scripts
+    Json,
+
+    /// A Waybar custom module object with `text`, `tooltip`, `class` and `alt`
+    Waybar,
+
+    /// The i3blocks line protocol: full text, short text and color
+    I3blocks,
+}
+
+/// Formats `game_status` for printing, without a trailing newline.
+/// ## Returns
+/// The formatted status, or `Error::InvalidResponse` if it couldn't be serialized.
+pub fn render(game_status: &GameStatus, format: StatusFormat) -> Result<String, Error> {
+    let state = game_status.state();
+    let text = format!("{} {}", game_status.friendly_name, state);
+    Ok(match format {
+        StatusFormat::Plain => game_status.to_string(),
+        StatusFormat::Table => [
+            ("Server", format!("{} ({})", game_status.friendly_name, game_status.name)),
+            ("Region", game_status.region.0.to_string()),
+            ("State", state.to_string()),
+            ("Version", game_status.game_version.clone()),
+            ("Message", game_status.message_code.to_string()),
+        ]
+        .iter()
+        .map(|(key, value)| format!("{:<8} {}", key, value))
+        .collect::<Vec<_>>()
+        .join("\n"),
+        StatusFormat::Json => serde_json::to_string_pretty(&json!({
+            "id": game_status.id,
+            "name": game_status.name,
+            "friendlyName": game_status.friendly_name,
+            "regionId": game_status.region.0,
+            "state": class(state),
+            "online": game_status.online,
+            "updateInProgress": game_status.update_in_progress,
+            "gameVersion": game_status.game_version,
+            "messageCode": message_code(game_status.message_code),
+        }))?,
+        StatusFormat::Waybar => serde_json::to_string(&json!({
+            "text": text,
+            "tooltip": game_status.to_string(),
+            "class": class(state),
+            "alt": class(state),
+        }))?,
+        StatusFormat::I3blocks => format!("{}\n{}\n{}", game_status, text, color(state)),
+    })
+}
+
+/// CSS class and machine name of `state`.
+fn class(state: ServerState) -> &'static str {
+    match state {
+        ServerState::Online => "online",
+        ServerState::Updating => "updating",
+        ServerState::Offline => "offline",
+    }
+}
+
+/// i3bar color of `state`.
+fn color(state: ServerState) -> &'static str {
+    match state {
+        ServerState::Online => "#00FF00",
+        ServerState::Updating => "#FFFF00",
+        ServerState::Offline => "#FF0000",
+    }
+}
+
+/// The `messageCode` the server sent.
+fn message_code(code: MessageCode) -> i16 {
+    match code {
+        MessageCode::None => 0,
+        MessageCode::Unknown(code) => code,
+    }
 }
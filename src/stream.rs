@@ -0,0 +1,80 @@
+use crate::error::Error;
+use sha2::Digest;
+use std::io::{self, ErrorKind, Read, Write};
+use std::time::Duration;
+
+/// Size of the buffer every chunk is copied through, memory use stays at this regardless of the
+/// size of the file.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Copies `reader` into `writer` chunk by chunk, feeding every chunk to `hasher` on the way.
+/// `position` is the amount of bytes already written before this copy (e.g. when resuming), and
+/// `on_progress` is called with the new total after every chunk.
+/// ## Returns
+/// The total amount of bytes, including `position`.
+pub fn copy_hashed<R: Read, W: Write, D: Digest>(
+    reader: &mut R,
+    writer: &mut W,
+    hasher: &mut D,
+    mut position: u64,
+    on_progress: &mut dyn FnMut(u64),
+) -> io::Result<u64> {
+    let mut buffer = vec![0; CHUNK_SIZE];
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+
+        writer.write_all(&buffer[..read])?;
+        hasher.update(&buffer[..read]);
+        position += read as u64;
+        on_progress(position);
+    }
+
+    writer.flush()?;
+    Ok(position)
+}
+
+/// Feeds everything from `reader` into `hasher` without keeping it around.
+/// ## Returns
+/// The amount of bytes hashed.
+pub fn hash_reader<R: Read, D: Digest>(reader: &mut R, hasher: &mut D) -> io::Result<u64> {
+    copy_hashed(reader, &mut io::sink(), hasher, 0, &mut |_| ())
+}
+
+/// Same as `copy_hashed`, with the chunks of an HTTP response as they arrive. Gives up once no
+/// chunk arrived for `read_timeout`, however long the whole body takes.
+/// ## Returns
+/// The total amount of bytes, including `position`, or `Error::Timeout` if the server stalled.
+pub async fn copy_response_hashed<W: Write, D: Digest>(
+    response: &mut reqwest::Response,
+    writer: &mut W,
+    hasher: &mut D,
+    mut position: u64,
+    read_timeout: Duration,
+    on_progress: &mut (dyn FnMut(u64) + Send),
+) -> Result<u64, Error> {
+    loop {
+        let chunk = match tokio::time::timeout(read_timeout, response.chunk()).await {
+            Ok(chunk) => chunk?,
+            Err(_) => {
+                return Err(Error::Timeout(format!(
+                    "{} sent no data for {:?}",
+                    response.url(),
+                    read_timeout
+                )))
+            }
+        };
+        let Some(chunk) = chunk else { break };
+        writer.write_all(&chunk)?;
+        hasher.update(&chunk);
+        position += chunk.len() as u64;
+        on_progress(position);
+    }
+
+    writer.flush()?;
+    Ok(position)
+}
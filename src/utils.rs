@@ -1,11 +1,16 @@
-use anyhow::Error;
+use crate::stream;
+use crate::error::Error;
 use rand::random;
+use serde::{Deserialize, Deserializer};
 use sha2::{Digest, Sha256};
-use std::fs::File;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::env;
+use std::fs::{self, File, OpenOptions};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-/**
+/*
  * Original function used by them for deviceid is:
  * This function gets the OS native UUID/GUID asynchronously (recommended), hashed by default.
  * @param {boolean} [original=false] - If true return original value of machine id, otherwise return hashed value (sha - 256)
@@ -14,7 +19,6 @@ use std::path::PathBuf;
 /// Blatantly fakes the device id, cause why the fuck do they need that for launcher?
 /// ## Returns
 /// A `String` containing TOTALLY LEGIT device id ;).
-
 #[inline(always)]
 pub fn get_fake_device_id() -> String {
     let mut hasher = Sha256::new();
@@ -23,16 +27,131 @@ pub fn get_fake_device_id() -> String {
 }
 
 #[inline(always)]
-pub fn write_to_file(path: &PathBuf, data: String) -> Result<(), Error> {
-    println!("Saving data to file: {}", path.display());
-    let mut file = match File::create(path) {
-        Ok(file) => file,
-        Err(e) => return Err(Error::from(e)),
-    };
+pub fn write_to_file(path: &Path, data: String) -> Result<(), Error> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = File::create(path)?;
 
     // Try to write the data to the file and handle potential errors
-    match file.write_all(data.as_ref()) {
-        Ok(_) => Ok(()),
-        Err(e) => Err(Error::from(e))
+    file.write_all(data.as_ref())?;
+    Ok(())
+}
+
+/// Writes `data` to `path` so that only the current user can read it, for secrets like the
+/// account vault and cached sessions. On unix the file is restricted to mode `0600`, also when
+/// it already existed with a looser mode. Windows gets no such protection, the file is only as
+/// private as the folder it is in, which is the user profile by default.
+pub fn write_private_file(path: &Path, data: &[u8]) -> Result<(), Error> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+
+    // The mode above only applies to newly created files
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
     }
+    file.write_all(data)?;
+    Ok(())
+}
+
+/// Hashes a file on disk without loading it into memory.
+/// ## Returns
+/// The hex encoded SHA-256 of the file contents.
+#[inline(always)]
+pub fn hash_file(path: &Path) -> Result<String, Error> {
+    let mut hasher = Sha256::new();
+    stream::hash_reader(&mut File::open(path)?, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Path of the partial file used while `path` is being downloaded.
+#[inline(always)]
+pub fn part_path(path: &Path) -> PathBuf {
+    let mut part = path.as_os_str().to_owned();
+    part.push(".part");
+    PathBuf::from(part)
+}
+
+/// Reads the top level `key: value` pairs of a simple YAML document, such as electron-updater's
+/// `latest.yml`. Nested values and lists are skipped, surrounding quotes are stripped.
+pub fn parse_yaml_top_level(data: &str) -> HashMap<String, String> {
+    data.lines()
+        .filter(|line| !line.starts_with([' ', '\t', '-', '#']))
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| {
+            let value = value.trim();
+            let value = value
+                .strip_prefix('\'')
+                .and_then(|value| value.strip_suffix('\''))
+                .or_else(|| value.strip_prefix('"').and_then(|value| value.strip_suffix('"')))
+                .unwrap_or(value);
+            (key.trim().to_owned(), value.to_owned())
+        })
+        .collect()
+}
+
+/// Orders two dotted version numbers like `2.9.13` and `2.30.1` by their numeric parts, parts
+/// that aren't numbers count as `0`.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parts = |version: &str| -> Vec<u64> {
+        version
+            .split('.')
+            .map(|part| part.trim().parse().unwrap_or(0))
+            .collect()
+    };
+    parts(a).cmp(&parts(b))
+}
+
+/// Environment variable replacing the launcher folder in the user config folder.
+pub const CONFIG_DIR_ENV: &str = "SSO_CONFIG_DIR";
+
+/// Environment variable replacing the launcher folder in the user cache folder.
+pub const CACHE_DIR_ENV: &str = "SSO_CACHE_DIR";
+
+/// Folder for the config file and the account vault, `SSO_CONFIG_DIR` if set, otherwise
+/// `sso-launcher-rs` in the user config folder, e.g. `%APPDATA%/sso-launcher-rs`.
+#[inline(always)]
+pub fn config_dir() -> Option<PathBuf> {
+    match env::var_os(CONFIG_DIR_ENV) {
+        Some(dir) => Some(PathBuf::from(dir)),
+        None => dirs::config_dir().map(|dir| dir.join("sso-launcher-rs")),
+    }
+}
+
+/// Folder for data that can be thrown away at any time, like the last known launcher version.
+/// `SSO_CACHE_DIR` if set, otherwise `sso-launcher-rs` in the user cache folder.
+#[inline(always)]
+pub fn cache_dir() -> Option<PathBuf> {
+    match env::var_os(CACHE_DIR_ENV) {
+        Some(dir) => Some(PathBuf::from(dir)),
+        None => dirs::cache_dir().map(|dir| dir.join("sso-launcher-rs")),
+    }
+}
+
+/// Deserializes an optional ID that the API sends either as a number or as a string.
+pub fn deserialize_id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Id {
+        Number(serde_json::Number),
+        Text(String),
+    }
+
+    Ok(Option::<Id>::deserialize(deserializer)?.map(|id| match id {
+        Id::Number(id) => id.to_string(),
+        Id::Text(id) => id,
+    }))
 }
@@ -0,0 +1,156 @@
+use crate::error::Error;
+use crate::utils;
+use argon2::Argon2;
+use base64::prelude::{Engine, BASE64_STANDARD};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Format version written to new vault files.
+const VAULT_VERSION: u32 = 1;
+
+/// Login credentials stored under an alias, `Debug` leaves the password out.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Account {
+    /// The email used to log in
+    pub email: String,
+
+    /// The password used to log in
+    pub password: String,
+}
+
+/// Accounts stored in an encrypted file, unlocked with a master passphrase.
+/// The key is derived from the passphrase with Argon2id, the accounts are sealed with
+/// XChaCha20-Poly1305. A wrong passphrase and a tampered file both fail to decrypt.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Vault {
+    /// Accounts by alias
+    accounts: BTreeMap<String, Account>,
+}
+
+impl std::fmt::Debug for Account {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Account")
+            .field("email", &self.email)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+/// On-disk layout of the vault, every binary field is base64 encoded.
+#[derive(Serialize, Deserialize)]
+struct VaultFile {
+    version: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl Vault {
+    /// `accounts.vault` in the launcher config folder, next to `config.toml`.
+    pub fn default_path() -> Option<PathBuf> {
+        utils::config_dir().map(|dir| dir.join("accounts.vault"))
+    }
+
+    /// Decrypts the vault at `path`, a missing file is an empty vault.
+    /// ## Returns
+    /// The vault, or `Error::Credentials` if the passphrase is wrong or the file is damaged.
+    pub fn open(path: &Path, passphrase: &str) -> Result<Self, Error> {
+        let data = match fs::read_to_string(path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vault::default()),
+            Err(e) => return Err(Error::from(e)),
+        };
+
+        let file: VaultFile = serde_json::from_str(&data).map_err(|e| {
+            Error::InvalidResponse(format!("Could not parse vault '{}': {}", path.display(), e))
+        })?;
+        if file.version != VAULT_VERSION {
+            return Err(Error::InvalidResponse(format!(
+                "Unsupported vault version {} in '{}'",
+                file.version,
+                path.display()
+            )));
+        }
+
+        let decode = |field: &str, value: &str| {
+            BASE64_STANDARD.decode(value).map_err(|e| {
+                Error::InvalidResponse(format!("Invalid {} in vault '{}': {}", field, path.display(), e))
+            })
+        };
+        let salt = decode("salt", &file.salt)?;
+        let nonce = decode("nonce", &file.nonce)?;
+        let ciphertext = decode("ciphertext", &file.ciphertext)?;
+        if nonce.len() != 24 {
+            return Err(Error::InvalidResponse(format!(
+                "Invalid nonce in vault '{}'",
+                path.display()
+            )));
+        }
+
+        let plaintext = cipher(passphrase, &salt)?
+            .decrypt(XNonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| {
+                Error::Credentials(format!(
+                    "Couldn't unlock '{}', wrong passphrase or damaged vault",
+                    path.display()
+                ))
+            })?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    /// Encrypts the vault with a fresh salt and nonce and writes it to `path`, readable only by
+    /// the current user.
+    pub fn save(&self, path: &Path, passphrase: &str) -> Result<(), Error> {
+        let salt: [u8; 16] = rand::random();
+        let nonce: [u8; 24] = rand::random();
+        let plaintext = serde_json::to_vec(self)?;
+        let ciphertext = cipher(passphrase, &salt)?
+            .encrypt(XNonce::from_slice(&nonce), plaintext.as_slice())
+            .map_err(|_| Error::Credentials("Couldn't encrypt the vault".to_string()))?;
+
+        let file = VaultFile {
+            version: VAULT_VERSION,
+            salt: BASE64_STANDARD.encode(salt),
+            nonce: BASE64_STANDARD.encode(nonce),
+            ciphertext: BASE64_STANDARD.encode(ciphertext),
+        };
+
+        utils::write_private_file(path, serde_json::to_string_pretty(&file)?.as_bytes())
+    }
+
+    /// Stores `account` under `alias`, replacing an existing account with the same alias.
+    pub fn add(&mut self, alias: String, account: Account) {
+        self.accounts.insert(alias, account);
+    }
+
+    /// Removes the account stored under `alias`.
+    /// ## Returns
+    /// The removed account, or `None` if there was none.
+    pub fn remove(&mut self, alias: &str) -> Option<Account> {
+        self.accounts.remove(alias)
+    }
+
+    /// The account stored under `alias`.
+    pub fn get(&self, alias: &str) -> Option<&Account> {
+        self.accounts.get(alias)
+    }
+
+    /// Every stored account, sorted by alias.
+    pub fn accounts(&self) -> impl Iterator<Item = (&String, &Account)> {
+        self.accounts.iter()
+    }
+}
+
+/// Derives the vault key from `passphrase` and `salt`.
+fn cipher(passphrase: &str, salt: &[u8]) -> Result<XChaCha20Poly1305, Error> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| Error::Credentials(format!("Couldn't derive the vault key: {}", e)))?;
+    Ok(XChaCha20Poly1305::new(&key.into()))
+}
@@ -0,0 +1,169 @@
+use crate::api::ManifestFile;
+use crate::async_api::AsyncStarStableApi;
+use crate::download::{
+    download_files, get_local_manifest, install_file_path, installed_manifest,
+    store_local_manifest, ProgressHandler, TransferOptions,
+};
+use crate::error::Error;
+use crate::utils::hash_file;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Which install `verify_game` and `repair_game` check.
+#[derive(Debug, Clone)]
+pub struct VerifyOptions {
+    /// Version to check against, defaults to the installed version
+    pub version: Option<String>,
+
+    /// The Star Stable Online base installation folder
+    pub install_path: PathBuf,
+
+    /// How damaged files are transferred on repair
+    pub transfer: TransferOptions,
+}
+
+/// Result of checking the installed files against a manifest.
+#[derive(Debug, Default)]
+pub struct VerifyReport {
+    /// Version the install was checked against
+    pub version: String,
+
+    /// Every file of the manifest of `version`
+    pub files: Vec<ManifestFile>,
+
+    /// Files listed in the manifest that are not on disk
+    pub missing: Vec<ManifestFile>,
+
+    /// Files whose size or hash doesn't match the manifest
+    pub modified: Vec<ManifestFile>,
+
+    /// Files on disk that the manifest doesn't know about, relative to the install folder
+    pub extra: Vec<String>,
+}
+
+impl VerifyReport {
+    /// If every file of the manifest is present and unmodified, extra files are ignored.
+    pub fn is_intact(&self) -> bool {
+        self.missing.is_empty() && self.modified.is_empty()
+    }
+
+    /// Every file that has to be downloaded again to repair the install.
+    pub fn damaged(&self) -> impl Iterator<Item = &ManifestFile> {
+        self.missing.iter().chain(self.modified.iter())
+    }
+}
+
+/// Hashes every installed file and compares it against the remote manifest of the installed version.
+/// ## Returns
+/// The report, a damaged install is not an error here.
+pub async fn verify_game(api: &AsyncStarStableApi, args: &VerifyOptions) -> Result<VerifyReport, Error> {
+    let (version, files) = fetch_manifest_files(api, &args.install_path, args).await?;
+    api.log(&format!("Verifying {} game files...", files.len()));
+
+    // Hashing the install takes a while, keep it off the runtime threads
+    let install_path = args.install_path.clone();
+    let mut report = tokio::task::spawn_blocking(move || verify_files(&install_path, &files))
+        .await
+        .map_err(|e| Error::Io(io::Error::other(e)))??;
+    report.version = version;
+    Ok(report)
+}
+
+/// Re-downloads only the missing and modified files of `report` from `verify_game`, an intact
+/// install is left alone.
+pub async fn repair_game(
+    api: &AsyncStarStableApi,
+    args: &VerifyOptions,
+    report: &VerifyReport,
+    on_progress: &ProgressHandler<'_>,
+) -> Result<(), Error> {
+    if report.is_intact() {
+        return Ok(());
+    }
+
+    let path = &args.install_path;
+    let damaged: Vec<&ManifestFile> = report.damaged().collect();
+    download_files(api, &report.version, path, &damaged, &args.transfer, on_progress).await?;
+
+    store_local_manifest(path, &installed_manifest(report.version.clone(), report.files.clone()))
+}
+
+/// Fetches the remote file list for the version override, or the installed version.
+async fn fetch_manifest_files(
+    api: &AsyncStarStableApi,
+    install_path: &Path,
+    args: &VerifyOptions,
+) -> Result<(String, Vec<ManifestFile>), Error> {
+    let version_hash = match &args.version {
+        Some(version) => version.to_owned(),
+        None => {
+            match get_local_manifest(install_path)? {
+                Some(local_manifest) => local_manifest.client.version,
+                None => {
+                    return Err(Error::InstallMissing(format!(
+                        "No installed game version found at '{}', use download-game or pass --version",
+                        install_path.display()
+                    )))
+                }
+            }
+        }
+    };
+
+    let remote_manifest = api.get_remote_manifest(version_hash.clone()).await?;
+    Ok((version_hash, remote_manifest.files))
+}
+
+/// Checks every manifest file on disk and collects files the manifest doesn't list. The
+/// `version` of the report is left empty.
+pub fn verify_files(install_path: &Path, files: &[ManifestFile]) -> Result<VerifyReport, Error> {
+    let mut report = VerifyReport::default();
+
+    for file in files {
+        let target = install_file_path(install_path, &file.path)?;
+        if !target.is_file() {
+            report.missing.push(file.clone());
+            continue;
+        }
+
+        let intact = if file.hash.is_empty() {
+            fs::metadata(&target)?.len() == file.size
+        } else {
+            hash_file(&target)?.eq_ignore_ascii_case(&file.hash)
+        };
+
+        if !intact {
+            report.modified.push(file.clone());
+        }
+    }
+
+    let known: HashSet<&str> = files.iter().map(|file| file.path.as_str()).collect();
+    let mut installed = vec![];
+    collect_files(install_path, install_path, &mut installed)?;
+    report.extra = installed
+        .into_iter()
+        .filter(|path| path != "manifest.json" && !known.contains(path.as_str()))
+        .collect();
+    report.files = files.to_vec();
+
+    Ok(report)
+}
+
+/// Recursively lists every file below `dir` as a `/` separated path relative to `base`.
+fn collect_files(base: &Path, dir: &Path, files: &mut Vec<String>) -> Result<(), Error> {
+    for entry in fs::read_dir(dir)? {
+        let path: PathBuf = entry?.path();
+        if path.is_dir() {
+            collect_files(base, &path, files)?;
+        } else if let Ok(relative) = path.strip_prefix(base) {
+            let relative: Vec<String> = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            files.push(relative.join("/"));
+        }
+    }
+
+    Ok(())
+}
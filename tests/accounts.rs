@@ -0,0 +1,158 @@
+mod common;
+
+use common::{stderr, stdout, MockServer, EMAIL, PASSWORD};
+use std::fs;
+use std::path::Path;
+use std::process::Output;
+
+const PASSPHRASE: &str = "correct horse battery staple";
+
+/// Runs the launcher with the vault passphrase set, without any credentials.
+fn run(server: &MockServer, home: &Path, passphrase: &str, args: &[&str]) -> Output {
+    server
+        .launcher(home)
+        .env("SSO_VAULT_PASSPHRASE", passphrase)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// Stores the mock account as `main`.
+fn add_main(server: &MockServer, home: &Path) {
+    let output = server
+        .launcher(home)
+        .env("SSO_VAULT_PASSPHRASE", PASSPHRASE)
+        .env("SSO_PASSWORD", PASSWORD)
+        .args(["--email", EMAIL, "accounts", "add", "main"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", stderr(&output));
+}
+
+#[test]
+fn stored_account_logs_in_by_alias() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+    add_main(&server, home.path());
+
+    let vault = fs::read_to_string(home.path().join("config/accounts.vault")).unwrap();
+    assert!(!vault.contains(EMAIL));
+    assert!(!vault.contains(PASSWORD));
+
+    let output = run(&server, home.path(), PASSPHRASE, &["accounts", "list"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains(EMAIL));
+
+    let output = run(&server, home.path(), PASSPHRASE, &["--account", "main", "status"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Mock Server"));
+}
+
+#[test]
+fn cached_session_of_an_account_skips_the_vault() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+    add_main(&server, home.path());
+
+    let output = run(&server, home.path(), PASSPHRASE, &["--account", "main", "status"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let output = server
+        .launcher(home.path())
+        .args(["--account", "main", "status"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Mock Server"));
+}
+
+#[test]
+fn wrong_passphrase_doesnt_unlock_the_vault() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+    add_main(&server, home.path());
+
+    let output = run(&server, home.path(), "wrong", &["--account", "main", "status"]);
+    assert_eq!(output.status.code(), Some(14), "{}", stderr(&output));
+    assert!(server.state().requests.is_empty());
+}
+
+#[test]
+fn removed_account_is_gone() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+    add_main(&server, home.path());
+
+    let output = run(&server, home.path(), PASSPHRASE, &["accounts", "remove", "main"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let output = run(&server, home.path(), PASSPHRASE, &["--account", "main", "status"]);
+    assert_eq!(output.status.code(), Some(14), "{}", stderr(&output));
+}
+
+#[test]
+fn explicit_email_wins_over_exported_account() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+    add_main(&server, home.path());
+
+    let output = server
+        .launcher(home.path())
+        .env("SSO_ACCOUNT", "missing")
+        .env("SSO_VAULT_PASSPHRASE", PASSPHRASE)
+        .env("SSO_PASSWORD", PASSWORD)
+        .args(["--email", EMAIL, "accounts", "add", "second"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let output = server
+        .launcher(home.path())
+        .env("SSO_ACCOUNT", "missing")
+        .env("SSO_PASSWORD", PASSWORD)
+        .args(["--email", EMAIL, "status"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let output = server
+        .launcher(home.path())
+        .env("SSO_ACCOUNT", "main")
+        .env("SSO_VAULT_PASSPHRASE", PASSPHRASE)
+        .args(["--no-session-cache", "status"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", stderr(&output));
+}
+
+#[test]
+fn account_flag_conflicts_with_email_flag() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+
+    let output = run(
+        &server,
+        home.path(),
+        PASSPHRASE,
+        &["--account", "main", "--email", EMAIL, "status"],
+    );
+    assert_eq!(output.status.code(), Some(2), "{}", stderr(&output));
+}
+
+#[test]
+fn debug_output_leaves_passwords_out() {
+    use sso_launcher_rs::vault::Account;
+    use sso_launcher_rs::Vault;
+
+    let mut vault = Vault::default();
+    vault.add(
+        "main".to_string(),
+        Account {
+            email: EMAIL.to_string(),
+            password: PASSWORD.to_string(),
+        },
+    );
+    let debug = format!("{:?}", vault);
+    assert!(debug.contains(EMAIL));
+    assert!(!debug.contains(PASSWORD));
+}
@@ -0,0 +1,530 @@
+//! Local stand-in for the Star Stable back-ends, so the launcher can be tested without touching
+//! production. Serves the endpoints documented in `src/endpoints.rs` from a single port:
+//!
+//! - `POST /launcher/auth/`
+//! - `POST /launcher/login-queue/v2/desktop/<launcher_hash>`
+//! - `GET /launcher/game-server/<launcher_hash>`
+//! - `GET /files/<version>/Manifest.json` and `GET /files/<version>/<path>`
+//! - `GET /launcher-files/latest/latest.yml` and the installer it points to
+//!
+//! Failure modes are scripted through the shared `MockState`.
+#![allow(dead_code)]
+
+use sha2::{Digest, Sha256};
+use sso_launcher_rs::Endpoints;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::process::{Command, Output};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+pub const EMAIL: &str = "rider@example.com";
+pub const PASSWORD: &str = "hunter2";
+pub const ACCOUNT_ID: u64 = 1337;
+pub const LAUNCHER_HASH: &str = "mock-launcher-hash";
+pub const QUEUE_TOKEN: &str = "mock-queue-token";
+pub const GAME_VERSION: &str = "2.0.0";
+pub const CHALLENGE_ID: &str = "mock-challenge";
+
+/// Scriptable behaviour of the mock server.
+#[derive(Debug, Clone)]
+pub struct MockState {
+    /// Password the auth endpoint accepts for `EMAIL`
+    pub password: String,
+
+    /// Launcher hash handed out on login, requests with any other hash are answered with `401`
+    pub launcher_hash: String,
+
+    /// Auth endpoint refuses every login with this `error` code and `message`
+    pub login_error: Option<(String, String)>,
+
+    /// Auth endpoint issues an email challenge that has to be answered with this code
+    pub verification_code: Option<String>,
+
+    /// Queue endpoint answers with `success: false`
+    pub queue_rejected: bool,
+
+    /// Amount of queue requests answered with `passedTheQueue: false` before passing
+    pub queue_polls_before_pass: usize,
+
+    /// Game server `online` flag
+    pub online: bool,
+
+    /// Game server `updateInProgress` flag
+    pub update_in_progress: bool,
+
+    /// Version the game server reports
+    pub game_version: String,
+
+    /// Game server `messageCode`
+    pub message_code: i16,
+
+    /// Game server `regionId`
+    pub region_id: i8,
+
+    /// Files per game version, keyed by path relative to the install folder
+    pub versions: HashMap<String, Vec<(String, Vec<u8>)>>,
+
+    /// Launcher version published in `latest.yml`
+    pub launcher_version: String,
+
+    /// Installer file name published in `latest.yml`
+    pub launcher_path: String,
+
+    /// `launcherVersion` of every login request received
+    pub sent_launcher_versions: Vec<String>,
+
+    /// Installer served as the official launcher
+    pub launcher: Vec<u8>,
+
+    /// `sha512` published in `latest.yml` instead of the one of `launcher`
+    pub launcher_sha512: Option<String>,
+
+    /// The installer is sent a few bytes at a time with this pause in between
+    pub launcher_trickle: Option<Duration>,
+
+    /// Amount of upcoming requests answered with `503 Service Unavailable`
+    pub server_errors: usize,
+
+    /// Every request received, as `METHOD /path`
+    pub requests: Vec<String>,
+}
+
+impl Default for MockState {
+    fn default() -> Self {
+        let mut versions = HashMap::new();
+        versions.insert(
+            GAME_VERSION.to_string(),
+            vec![
+                ("client/SSOClient.exe".to_string(), b"game executable".to_vec()),
+                ("client/data/horses.pak".to_string(), b"lots of horses".to_vec()),
+            ],
+        );
+
+        MockState {
+            password: PASSWORD.to_string(),
+            launcher_hash: LAUNCHER_HASH.to_string(),
+            login_error: None,
+            verification_code: None,
+            queue_rejected: false,
+            queue_polls_before_pass: 0,
+            online: true,
+            update_in_progress: false,
+            game_version: GAME_VERSION.to_string(),
+            message_code: 0,
+            region_id: 1,
+            versions,
+            launcher_version: "2.31.0".to_string(),
+            launcher_path: "Star Stable Online Setup.exe".to_string(),
+            sent_launcher_versions: vec![],
+            launcher: b"official launcher installer".to_vec(),
+            launcher_sha512: None,
+            launcher_trickle: None,
+            server_errors: 0,
+            requests: vec![],
+        }
+    }
+}
+
+/// Running mock server, lives until the test process exits.
+pub struct MockServer {
+    pub url: String,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockServer {
+    pub fn start() -> Self {
+        Self::start_with(MockState::default())
+    }
+
+    pub fn start_with(state: MockState) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Couldn't bind mock server");
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let state = Arc::new(Mutex::new(state));
+
+        let server_state = state.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let state = server_state.clone();
+                thread::spawn(move || handle(stream, &state));
+            }
+        });
+
+        MockServer { url, state }
+    }
+
+    pub fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap()
+    }
+
+    /// Back-end URLs pointing at this server, for using the library directly.
+    pub fn endpoints(&self) -> Endpoints {
+        Endpoints {
+            auth_login: format!("{}launcher/auth/", self.url),
+            auth_queue_create: format!("{}launcher/login-queue/v2/desktop/", self.url),
+            launcher_proxy: self.url.clone(),
+            game_server_data: format!("{}launcher/game-server/", self.url),
+            game_files: format!("{}files/", self.url),
+            launcher_files: format!("{}launcher-files/", self.url),
+            metrics: format!("{}metrics/", self.url),
+        }
+    }
+
+    /// Command running the launcher binary against this server, with the config and cache
+    /// folders pointed at `home` through `SSO_CONFIG_DIR` and `SSO_CACHE_DIR`, so no real config,
+    /// vault or session is picked up on any platform.
+    pub fn launcher(&self, home: &Path) -> Command {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_sso-launcher-rs"));
+        command
+            .env_clear()
+            .env("PATH", std::env::var_os("PATH").unwrap_or_default())
+            .env("HOME", home)
+            .env("SSO_CONFIG_DIR", home.join("config"))
+            .env("SSO_CACHE_DIR", home.join("cache"))
+            .env("SSO_LAUNCHER_PROXY_URL", &self.url)
+            .env("SSO_GAME_FILES_URL", format!("{}files/", self.url))
+            .env("SSO_LAUNCHER_FILES_URL", format!("{}launcher-files/", self.url))
+            .env("SSO_METRICS_URL", format!("{}metrics/", self.url));
+        command
+    }
+
+    /// Runs the launcher with the mock credentials followed by `args`, the password is passed
+    /// through `SSO_PASSWORD`.
+    pub fn run(&self, home: &Path, args: &[&str]) -> Output {
+        self.launcher(home)
+            .env("SSO_PASSWORD", PASSWORD)
+            .args(["--email", EMAIL])
+            .args(args)
+            .output()
+            .expect("Couldn't run launcher binary")
+    }
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+pub fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+pub fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+struct Request {
+    method: String,
+    path: String,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+struct Response {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+
+    /// Pause between the pieces of the body, sent all at once without one
+    trickle: Option<Duration>,
+}
+
+impl Response {
+    fn json(value: serde_json::Value) -> Self {
+        Response {
+            status: 200,
+            headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+            body: value.to_string().into_bytes(),
+            trickle: None,
+        }
+    }
+
+    fn bytes(data: &[u8]) -> Self {
+        Response {
+            status: 200,
+            headers: vec![],
+            body: data.to_vec(),
+            trickle: None,
+        }
+    }
+
+    fn status(status: u16) -> Self {
+        Response {
+            status,
+            headers: vec![],
+            body: vec![],
+            trickle: None,
+        }
+    }
+}
+
+fn handle(stream: TcpStream, state: &Mutex<MockState>) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let request = match read_request(&mut reader) {
+        Some(request) => request,
+        None => return,
+    };
+
+    let response = {
+        let mut state = state.lock().unwrap();
+        state
+            .requests
+            .push(format!("{} {}", request.method, request.path));
+        route(&mut state, &request)
+    };
+    write_response(stream, response);
+}
+
+fn read_request(reader: &mut BufReader<TcpStream>) -> Option<Request> {
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let path = parts.next()?.to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).ok()?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let length = headers
+        .get("content-length")
+        .and_then(|length| length.parse().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).ok()?;
+
+    Some(Request {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+fn write_response(mut stream: TcpStream, response: Response) {
+    let mut head = format!(
+        "HTTP/1.1 {} Mock\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        response.body.len()
+    );
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+
+    let _ = stream.write_all(head.as_bytes());
+    match response.trickle {
+        None => {
+            let _ = stream.write_all(&response.body);
+        }
+        Some(pause) => {
+            for piece in response.body.chunks(4) {
+                let _ = stream.write_all(piece);
+                let _ = stream.flush();
+                thread::sleep(pause);
+            }
+        }
+    }
+}
+
+fn route(state: &mut MockState, request: &Request) -> Response {
+    if state.server_errors > 0 {
+        state.server_errors -= 1;
+        return Response::status(503);
+    }
+
+    let path = request.path.as_str();
+    match request.method.as_str() {
+        "POST" if path == "/launcher/auth/" => login(state, request),
+        "POST" if path.starts_with("/launcher/login-queue/v2/desktop/") => queue(state, path),
+        "GET" if path.starts_with("/launcher/game-server/") => game_server(state, path),
+        "GET" if path == "/launcher-files/latest/latest.yml" => latest_yml(state),
+        "GET" if path == "/launcher-files/latest/Star%20Stable%20Online%20Setup.exe" => Response {
+            trickle: state.launcher_trickle,
+            ..serve_range(&state.launcher, request)
+        },
+        "GET" if path.starts_with("/files/") => game_files(state, request),
+        _ => Response::status(404),
+    }
+}
+
+fn login(state: &mut MockState, request: &Request) -> Response {
+    let body: serde_json::Value = match serde_json::from_slice(&request.body) {
+        Ok(body) => body,
+        Err(_) => return Response::status(400),
+    };
+    if let Some(version) = body["launcherVersion"].as_str() {
+        state.sent_launcher_versions.push(version.to_string());
+    }
+
+    if let Some((error, message)) = &state.login_error {
+        return Response::json(serde_json::json!({
+            "success": false,
+            "error": error,
+            "message": message,
+        }));
+    }
+
+    if body["username"] != EMAIL || body["password"] != state.password.as_str() {
+        return Response::json(serde_json::json!({
+            "success": false,
+            "error": "invalid_credentials",
+            "message": "Wrong username or password",
+        }));
+    }
+
+    if let Some(code) = &state.verification_code {
+        if body["challengeId"] != CHALLENGE_ID {
+            return Response::json(serde_json::json!({
+                "success": false,
+                "error": "verification_required",
+                "challenge": { "id": CHALLENGE_ID, "type": "email" },
+            }));
+        }
+
+        if body["verificationCode"] != code.as_str() {
+            return Response::json(serde_json::json!({
+                "success": false,
+                "error": "invalid_verification_code",
+            }));
+        }
+    }
+
+    Response::json(serde_json::json!({
+        "success": true,
+        "launcherHash": state.launcher_hash,
+        "accountId": ACCOUNT_ID,
+        "metricsUrl": "",
+        "metricsGroups": [1],
+    }))
+}
+
+fn queue(state: &mut MockState, path: &str) -> Response {
+    if !path.ends_with(state.launcher_hash.as_str()) {
+        return Response::status(401);
+    }
+
+    if state.queue_rejected {
+        return Response::json(serde_json::json!({ "success": false }));
+    }
+
+    let position = state.queue_polls_before_pass;
+    let passed = position == 0;
+    if !passed {
+        state.queue_polls_before_pass -= 1;
+    }
+
+    Response::json(serde_json::json!({
+        "success": true,
+        "passedTheQueue": passed,
+        "queueToken": QUEUE_TOKEN,
+        "queuePosition": position,
+        "estimatedWaitTime": position * 90,
+    }))
+}
+
+fn game_server(state: &MockState, path: &str) -> Response {
+    if !path.ends_with(state.launcher_hash.as_str()) {
+        return Response::status(401);
+    }
+
+    Response::json(serde_json::json!({
+        "id": 7,
+        "regionId": state.region_id,
+        "name": "mock-eu",
+        "friendlyName": "Mock Server",
+        "online": state.online,
+        "updateInProgress": state.update_in_progress,
+        "iconUrl": null,
+        "messageCode": state.message_code,
+        "gameVersion": state.game_version,
+    }))
+}
+
+fn latest_yml(state: &MockState) -> Response {
+    use base64::prelude::{Engine, BASE64_STANDARD};
+    let sha512 = match &state.launcher_sha512 {
+        Some(sha512) => sha512.clone(),
+        None => BASE64_STANDARD.encode(sha2::Sha512::digest(&state.launcher)),
+    };
+    Response::bytes(
+        format!(
+            "version: {version}\nfiles:\n  - url: {path}\n    sha512: {sha512}\n    size: {}\npath: {path}\nsha512: {sha512}\nreleaseDate: '2024-05-01T10:00:00.000Z'\n",
+            state.launcher.len(),
+            version = state.launcher_version,
+            path = state.launcher_path,
+            sha512 = sha512
+        )
+        .as_bytes(),
+    )
+}
+
+fn game_files(state: &MockState, request: &Request) -> Response {
+    let rest = &request.path["/files/".len()..];
+    let (version, file_path) = match rest.split_once('/') {
+        Some(parts) => parts,
+        None => return Response::status(404),
+    };
+
+    let files = match state.versions.get(version) {
+        Some(files) => files,
+        None => return Response::status(404),
+    };
+
+    if file_path == "Manifest.json" {
+        let entries: Vec<serde_json::Value> = files
+            .iter()
+            .map(|(path, data)| {
+                serde_json::json!({
+                    "path": path,
+                    "size": data.len(),
+                    "hash": sha256_hex(data),
+                })
+            })
+            .collect();
+        return Response::json(serde_json::json!({
+            "client": { "name": "client", "version": version },
+            "files": entries,
+        }));
+    }
+
+    match files.iter().find(|(path, _)| path == file_path) {
+        Some((_, data)) => serve_range(data, request),
+        None => Response::status(404),
+    }
+}
+
+/// Serves `data`, honouring a `Range: bytes=<start>-` header like a real file server.
+fn serve_range(data: &[u8], request: &Request) -> Response {
+    let start = request
+        .headers
+        .get("range")
+        .and_then(|range| range.strip_prefix("bytes="))
+        .and_then(|range| range.trim_end_matches('-').parse::<usize>().ok());
+
+    match start {
+        None => Response::bytes(data),
+        Some(start) if start >= data.len() => Response::status(416),
+        Some(start) => Response {
+            status: 206,
+            headers: vec![(
+                "Content-Range".to_string(),
+                format!("bytes {}-{}/{}", start, data.len() - 1, data.len()),
+            )],
+            body: data[start..].to_vec(),
+            trickle: None,
+        },
+    }
+}
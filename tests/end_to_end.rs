@@ -0,0 +1,195 @@
+mod common;
+
+use common::{sha256_hex, stderr, stdout, MockServer, GAME_VERSION};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Installs the current mock game version into `<home>/install` through `download-game`.
+fn install_game(server: &MockServer, home: &Path) -> PathBuf {
+    let install_path = home.join("install");
+    let output = server.run(
+        home,
+        &["download-game", "--install-path", install_path.to_str().unwrap()],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    install_path
+}
+
+/// Replaces the installed `SSOClient.exe` with a script writing its arguments to `args.txt`.
+#[cfg(unix)]
+fn fake_client(install_path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let exe = install_path.join("client").join("SSOClient.exe");
+    fs::write(
+        &exe,
+        "#!/bin/sh\nfor arg in \"$@\"; do echo \"$arg\"; done > args.txt\n",
+    )
+    .unwrap();
+    fs::set_permissions(&exe, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+#[test]
+fn download_game_installs_files_and_writes_local_manifest() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+    let install_path = install_game(&server, home.path());
+
+    assert_eq!(
+        fs::read(install_path.join("client/SSOClient.exe")).unwrap(),
+        b"game executable"
+    );
+    assert_eq!(
+        fs::read(install_path.join("client/data/horses.pak")).unwrap(),
+        b"lots of horses"
+    );
+
+    let manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(install_path.join("manifest.json")).unwrap())
+            .unwrap();
+    assert_eq!(manifest["client"]["version"], GAME_VERSION);
+    assert_eq!(manifest["files"].as_array().unwrap().len(), 2);
+    assert_eq!(
+        manifest["files"][1]["hash"],
+        sha256_hex(b"lots of horses").as_str()
+    );
+}
+
+#[test]
+fn download_game_only_fetches_changed_files_and_removes_obsolete_ones() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+    let install_path = install_game(&server, home.path());
+
+    {
+        let mut state = server.state();
+        state.game_version = "2.1.0".to_string();
+        state.versions.insert(
+            "2.1.0".to_string(),
+            vec![
+                ("client/SSOClient.exe".to_string(), b"game executable".to_vec()),
+                ("client/data/saddles.pak".to_string(), b"shiny saddles".to_vec()),
+            ],
+        );
+        state.requests.clear();
+    }
+
+    let output = server.run(
+        home.path(),
+        &["download-game", "--install-path", install_path.to_str().unwrap()],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Install state: update (1 added, 0 changed, 1 removed)"));
+
+    let requests = server.state().requests.clone();
+    assert!(requests.contains(&"GET /files/2.1.0/client/data/saddles.pak".to_string()));
+    assert!(!requests.contains(&"GET /files/2.1.0/client/SSOClient.exe".to_string()));
+    assert!(!install_path.join("client/data/horses.pak").exists());
+    assert!(install_path.join("client/data/saddles.pak").exists());
+}
+
+#[test]
+fn repair_redownloads_only_damaged_files() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+    let install_path = install_game(&server, home.path());
+    let install_arg = install_path.to_str().unwrap();
+    fs::write(install_path.join("client/data/horses.pak"), b"ponies").unwrap();
+    fs::write(install_path.join("client/settings.ini"), b"user settings").unwrap();
+
+    let output = server.run(home.path(), &["verify", "--install-path", install_arg]);
+    assert_eq!(output.status.code(), Some(12), "{}", stderr(&output));
+    assert!(stdout(&output).contains("client/data/horses.pak"));
+    assert!(stdout(&output).contains("client/settings.ini"));
+
+    server.state().requests.clear();
+    let output = server.run(home.path(), &["repair", "--install-path", install_arg]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        fs::read(install_path.join("client/data/horses.pak")).unwrap(),
+        b"lots of horses"
+    );
+    assert!(install_path.join("client/settings.ini").exists());
+    assert!(!server
+        .state()
+        .requests
+        .contains(&format!("GET /files/{}/client/SSOClient.exe", GAME_VERSION)));
+}
+
+#[test]
+fn launch_refuses_while_server_is_updating() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+    let install_path = install_game(&server, home.path());
+    server.state().update_in_progress = true;
+
+    let output = server.run(
+        home.path(),
+        &["launch", "--install-path", install_path.to_str().unwrap()],
+    );
+    assert_eq!(output.status.code(), Some(7), "{}", stderr(&output));
+    assert!(stderr(&output).contains("undergoing update"));
+}
+
+#[test]
+fn launch_refuses_on_version_mismatch() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+    let install_path = install_game(&server, home.path());
+    server.state().game_version = "9.9.9".to_string();
+
+    let output = server.run(
+        home.path(),
+        &["launch", "--install-path", install_path.to_str().unwrap()],
+    );
+    assert_eq!(output.status.code(), Some(8), "{}", stderr(&output));
+    assert!(stderr(&output).contains("'9.9.9'"));
+}
+
+#[test]
+fn launch_refuses_without_install() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+
+    let output = server.run(
+        home.path(),
+        &["launch", "--install-path", home.path().to_str().unwrap()],
+    );
+    assert_eq!(output.status.code(), Some(9), "{}", stderr(&output));
+}
+
+#[cfg(unix)]
+#[test]
+fn launch_passes_session_arguments_to_the_client() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+    let install_path = install_game(&server, home.path());
+    fake_client(&install_path);
+
+    let output = server.run(
+        home.path(),
+        &[
+            "launch",
+            "--install-path",
+            install_path.to_str().unwrap(),
+            "--language",
+            "sv",
+            "--debug",
+        ],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let client_path = install_path.join("client");
+    let args = fs::read_to_string(client_path.join("args.txt")).unwrap();
+    let expected = [
+        "-Language=sv".to_string(),
+        format!("-NetworkUserId={}", common::ACCOUNT_ID),
+        format!("-MetricsServer={}metrics/", server.url),
+        "-MetricsGroup=[1]".to_string(),
+        format!("-LoginQueueToken={}", common::QUEUE_TOKEN),
+        format!("-NetworkLauncherHash={}", common::LAUNCHER_HASH),
+        format!("-ProjectUserDataPath={}", client_path.display()),
+        format!("-NetworkLauncherServer={}", server.url),
+    ];
+    assert_eq!(args.lines().collect::<Vec<_>>(), expected);
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use common::{MockServer, ACCOUNT_ID, EMAIL, GAME_VERSION, PASSWORD, QUEUE_TOKEN};
+use sso_launcher_rs::download::{DownloadProgress, InstallType};
+use sso_launcher_rs::{download, verify};
+use sso_launcher_rs::{
+    AsyncStarStableApi, DownloadGameOptions, GameLauncher, HttpOptions, StarStableApi,
+    TransferOptions, VerifyOptions,
+};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+fn api(server: &MockServer) -> StarStableApi {
+    StarStableApi::new(server.endpoints(), &HttpOptions::default()).unwrap()
+}
+
+#[test]
+fn library_logs_in_and_builds_launch_arguments() {
+    let server = MockServer::start();
+    let messages = Arc::new(Mutex::new(Vec::new()));
+    let logged = messages.clone();
+    let api = api(&server).with_logger(move |message| logged.lock().unwrap().push(message.to_string()));
+
+    let (auth, status) = api
+        .login_with_status(EMAIL.to_string(), PASSWORD.to_string(), Some("2.30.1"))
+        .unwrap();
+    assert_eq!(status.game_version, GAME_VERSION);
+    assert!(messages
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|message| message.starts_with("Grabbing Queue Token")));
+
+    let args = GameLauncher::new("install")
+        .language("de")
+        .game_arguments(vec!["Windowed".to_string()])
+        .arguments(api.endpoints(), &auth);
+    assert_eq!(args.first().unwrap(), "-Language=de");
+    assert!(args.contains(&format!("-NetworkUserId={}", ACCOUNT_ID)));
+    assert!(args.contains(&format!("-LoginQueueToken={}", QUEUE_TOKEN)));
+    assert_eq!(args.last().unwrap(), "-Windowed");
+}
+
+#[test]
+fn library_installs_and_verifies_the_game() {
+    let server = MockServer::start();
+    let api = api(&server);
+    let home = tempfile::tempdir().unwrap();
+    let install_path = home.path().join("install");
+
+    let (_, status) = api
+        .login_with_status(EMAIL.to_string(), PASSWORD.to_string(), Some("2.30.1"))
+        .unwrap();
+    let options = DownloadGameOptions {
+        version: status.game_version,
+        install_path: install_path.clone(),
+        transfer: TransferOptions::default(),
+    };
+    let downloaded = AtomicU64::new(0);
+    let plan = api
+        .download_game(&options, &|progress| {
+            if let DownloadProgress::Advanced { bytes } = progress {
+                downloaded.fetch_add(bytes, Ordering::SeqCst);
+            }
+        })
+        .unwrap();
+    assert_eq!(plan.install_type, InstallType::FullInstall);
+    assert_eq!(plan.diff.added.len(), 2);
+    let total: u64 = plan.files.iter().map(|file| file.size).sum();
+    assert_eq!(downloaded.load(Ordering::SeqCst), total);
+
+    let options = VerifyOptions {
+        version: None,
+        install_path,
+        transfer: TransferOptions::default(),
+    };
+    let report = api.verify_game(&options).unwrap();
+    assert!(report.is_intact());
+    assert_eq!(report.version, GAME_VERSION);
+}
+
+#[tokio::test]
+async fn async_library_installs_and_verifies_the_game_inside_a_runtime() {
+    let server = MockServer::start();
+    let api = AsyncStarStableApi::new(server.endpoints(), &HttpOptions::default()).unwrap();
+    let home = tempfile::tempdir().unwrap();
+    let install_path = home.path().join("install");
+
+    // Spawning needs the futures to be `Send`, as frontends on a multi-threaded runtime do
+    let report = tokio::spawn(async move {
+        let (_, status) = api
+            .login_with_status(EMAIL.to_string(), PASSWORD.to_string(), Some("2.30.1"))
+            .await?;
+        let options = DownloadGameOptions {
+            version: status.game_version,
+            install_path: install_path.clone(),
+            transfer: TransferOptions::default(),
+        };
+        download::download_game(&api, &options, &|_| ()).await?;
+
+        let options = VerifyOptions {
+            version: None,
+            install_path,
+            transfer: TransferOptions::default(),
+        };
+        verify::verify_game(&api, &options).await
+    })
+    .await
+    .unwrap()
+    .unwrap();
+    assert!(report.is_intact());
+}
@@ -0,0 +1,427 @@
+mod common;
+
+use common::{stderr, stdout, MockServer, MockState, EMAIL, PASSWORD};
+use std::fs;
+use std::time::{Duration, Instant};
+
+#[test]
+fn status_reports_the_mock_game_server() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+
+    let output = server.run(home.path(), &["status"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Mock Server"));
+    assert!(server
+        .state()
+        .requests
+        .contains(&format!("GET /launcher/game-server/{}", common::LAUNCHER_HASH)));
+}
+
+#[test]
+fn wrong_password_exits_with_auth_code() {
+    let server = MockServer::start_with(MockState {
+        password: "something else".to_string(),
+        ..MockState::default()
+    });
+    let home = tempfile::tempdir().unwrap();
+
+    let output = server.run(home.path(), &["status"]);
+    assert_eq!(output.status.code(), Some(4), "{}", stderr(&output));
+    assert!(stderr(&output).contains("Wrong email or password"));
+}
+
+#[test]
+fn login_failure_reasons_have_distinct_exit_codes() {
+    for (error, code, hint) in [
+        ("account_locked", 15, "locked or banned"),
+        ("email_not_verified", 16, "verification mail"),
+        ("launcher_outdated", 17, "--launcher-version"),
+        ("rate_limited", 18, "Too many login attempts"),
+        ("maintenance", 19, "maintenance"),
+        ("something_new", 4, "'something_new'"),
+    ] {
+        let server = MockServer::start_with(MockState {
+            login_error: Some((error.to_string(), "Nope".to_string())),
+            ..MockState::default()
+        });
+        let home = tempfile::tempdir().unwrap();
+
+        let output = server.run(home.path(), &["status"]);
+        assert_eq!(output.status.code(), Some(code), "{}: {}", error, stderr(&output));
+        assert!(stderr(&output).contains(hint), "{}: {}", error, stderr(&output));
+        assert!(stderr(&output).contains("server said: Nope"));
+    }
+}
+
+#[test]
+fn rejected_queue_exits_with_queue_code() {
+    let server = MockServer::start_with(MockState {
+        queue_rejected: true,
+        ..MockState::default()
+    });
+    let home = tempfile::tempdir().unwrap();
+
+    let output = server.run(
+        home.path(),
+        &["launch", "--install-path", home.path().to_str().unwrap()],
+    );
+    assert_eq!(output.status.code(), Some(5), "{}", stderr(&output));
+}
+
+#[test]
+fn login_sends_launcher_version_from_latest_yml() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+
+    let output = server.run(home.path(), &["status"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(server
+        .state()
+        .requests
+        .contains(&"GET /launcher-files/latest/latest.yml".to_string()));
+
+    let cached = fs::read_to_string(home.path().join("cache/launcher_version"));
+    assert_eq!(cached.unwrap(), "2.31.0");
+
+    // Cached for a day
+    let output = server.run(home.path(), &["--no-session-cache", "status"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let fetches = server
+        .state()
+        .requests
+        .iter()
+        .filter(|request| *request == "GET /launcher-files/latest/latest.yml")
+        .count();
+    assert_eq!(fetches, 1);
+    assert_eq!(server.state().sent_launcher_versions, ["2.31.0", "2.31.0"]);
+}
+
+#[test]
+fn login_never_sends_a_launcher_version_below_the_fallback() {
+    let server = MockServer::start_with(MockState {
+        launcher_version: "2.9.13".to_string(),
+        ..MockState::default()
+    });
+    let home = tempfile::tempdir().unwrap();
+
+    let output = server.run(home.path(), &["--no-session-cache", "status"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let cache = home.path().join("cache/launcher_version");
+    assert_eq!(fs::read_to_string(&cache).unwrap(), "2.9.13");
+    fs::write(&cache, "2.31.4").unwrap();
+    let output = server.run(home.path(), &["--no-session-cache", "status"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(server.state().sent_launcher_versions, ["2.30.1", "2.31.4"]);
+}
+
+#[test]
+fn download_launcher_keeps_installer_matching_latest_yml() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+    let target = home.path().join("Setup.exe");
+
+    let output = server.run(
+        home.path(),
+        &["download-launcher", "--download-path", target.to_str().unwrap()],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(fs::read(&target).unwrap(), server.state().launcher);
+}
+
+#[test]
+fn download_launcher_refuses_installer_not_matching_latest_yml() {
+    use base64::prelude::{Engine, BASE64_STANDARD};
+    use sha2::Digest;
+    let server = MockServer::start_with(MockState {
+        launcher_sha512: Some(BASE64_STANDARD.encode(sha2::Sha512::digest(b"old installer"))),
+        ..MockState::default()
+    });
+    let home = tempfile::tempdir().unwrap();
+    let target = home.path().join("Setup.exe");
+
+    let output = server.run(
+        home.path(),
+        &["download-launcher", "--download-path", target.to_str().unwrap()],
+    );
+    assert_eq!(output.status.code(), Some(12), "{}", stderr(&output));
+    assert!(stderr(&output).contains("Hash mismatch"));
+    assert!(!target.exists());
+    assert!(!home.path().join("Setup.exe.part").exists());
+}
+
+#[test]
+fn download_launcher_keeps_installer_unverified_when_latest_yml_is_stale() {
+    use base64::prelude::{Engine, BASE64_STANDARD};
+    use sha2::Digest;
+    let server = MockServer::start_with(MockState {
+        launcher_version: "2.9.13".to_string(),
+        launcher_path: "Star Stable Online Setup 2.9.13.exe".to_string(),
+        launcher_sha512: Some(BASE64_STANDARD.encode(sha2::Sha512::digest(b"old installer"))),
+        ..MockState::default()
+    });
+    let home = tempfile::tempdir().unwrap();
+    let target = home.path().join("Setup.exe");
+
+    let output = server.run(
+        home.path(),
+        &["download-launcher", "--download-path", target.to_str().unwrap()],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("could not be verified"));
+    assert_eq!(fs::read(&target).unwrap(), server.state().launcher);
+}
+
+#[test]
+fn download_launcher_resumes_from_part_file() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+    let target = home.path().join("Setup.exe");
+    let launcher = server.state().launcher.clone();
+    fs::write(home.path().join("Setup.exe.part"), &launcher[..8]).unwrap();
+
+    let output = server.run(
+        home.path(),
+        &["download-launcher", "--download-path", target.to_str().unwrap()],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("Resuming"));
+    assert_eq!(fs::read(&target).unwrap(), launcher);
+}
+
+#[test]
+fn download_launcher_outlasts_the_read_timeout_while_data_arrives() {
+    let server = MockServer::start_with(MockState {
+        launcher_trickle: Some(Duration::from_millis(300)),
+        ..MockState::default()
+    });
+    let home = tempfile::tempdir().unwrap();
+    let target = home.path().join("Setup.exe");
+
+    let started = Instant::now();
+    let output = server.run(
+        home.path(),
+        &[
+            "--read-timeout",
+            "1",
+            "download-launcher",
+            "--download-path",
+            target.to_str().unwrap(),
+        ],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(started.elapsed() > Duration::from_secs(1));
+    assert_eq!(fs::read(&target).unwrap(), server.state().launcher);
+}
+
+#[test]
+fn download_launcher_gives_up_on_a_stalled_download() {
+    let server = MockServer::start_with(MockState {
+        launcher_trickle: Some(Duration::from_secs(3)),
+        ..MockState::default()
+    });
+    let home = tempfile::tempdir().unwrap();
+    let target = home.path().join("Setup.exe");
+
+    let output = server.run(
+        home.path(),
+        &[
+            "--read-timeout",
+            "1",
+            "download-launcher",
+            "--download-path",
+            target.to_str().unwrap(),
+        ],
+    );
+    assert_eq!(output.status.code(), Some(3), "{}", stderr(&output));
+    assert!(stderr(&output).contains("sent no data"));
+    assert!(!target.exists());
+}
+
+#[test]
+fn server_errors_are_retried() {
+    let server = MockServer::start_with(MockState {
+        server_errors: 2,
+        ..MockState::default()
+    });
+    let home = tempfile::tempdir().unwrap();
+
+    let output = server.run(home.path(), &["status"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("503"));
+}
+
+#[test]
+fn server_errors_fail_once_retries_run_out() {
+    let server = MockServer::start_with(MockState {
+        server_errors: usize::MAX,
+        ..MockState::default()
+    });
+    let home = tempfile::tempdir().unwrap();
+    let config = home.path().join("launcher.toml");
+    fs::write(&config, "launcher_version = \"2.30.1\"\n\n[http]\nretries = 1\n").unwrap();
+
+    let output = server.run(
+        home.path(),
+        &["--config", config.to_str().unwrap(), "status"],
+    );
+    assert_eq!(output.status.code(), Some(3), "{}", stderr(&output));
+    assert_eq!(server.state().requests.len(), 2);
+}
+
+#[test]
+fn download_launcher_runs_without_credentials() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+    let target = home.path().join("Setup.exe");
+
+    let output = server
+        .launcher(home.path())
+        .args(["download-launcher", "--download-path", target.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!server
+        .state()
+        .requests
+        .iter()
+        .any(|request| request.starts_with("POST /launcher/auth/")));
+}
+
+#[test]
+fn status_without_credentials_is_a_usage_error() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+
+    let output = server.launcher(home.path()).arg("status").output().unwrap();
+    assert_eq!(output.status.code(), Some(2), "{}", stderr(&output));
+    assert!(stderr(&output).contains("--email or --account is required"));
+    assert!(server.state().requests.is_empty());
+}
+
+#[test]
+fn waits_in_the_login_queue_until_passed() {
+    let server = MockServer::start_with(MockState {
+        queue_polls_before_pass: 2,
+        ..MockState::default()
+    });
+    let home = tempfile::tempdir().unwrap();
+
+    // Not installed, so the launch fails right after the queue has been passed
+    let output = server.run(
+        home.path(),
+        &["launch", "--install-path", home.path().to_str().unwrap()],
+    );
+    assert_eq!(output.status.code(), Some(9), "{}", stderr(&output));
+    assert!(stderr(&output).contains("Waiting in login queue, position 2, about 3m 0s left"));
+    assert!(stderr(&output).contains("position 1, about 1m 30s left"));
+
+    let polls = server
+        .state()
+        .requests
+        .iter()
+        .filter(|request| request.starts_with("POST /launcher/login-queue/"))
+        .count();
+    assert_eq!(polls, 3);
+}
+
+#[test]
+fn status_skips_the_login_queue() {
+    let server = MockServer::start_with(MockState {
+        queue_polls_before_pass: usize::MAX,
+        ..MockState::default()
+    });
+    let home = tempfile::tempdir().unwrap();
+
+    for args in [&["--no-session-cache", "status"][..], &["status"], &["status"]] {
+        let output = server.run(home.path(), args);
+        assert!(output.status.success(), "{}", stderr(&output));
+        assert!(stdout(&output).contains("Mock Server"));
+    }
+    assert!(!server
+        .state()
+        .requests
+        .iter()
+        .any(|request| request.starts_with("POST /launcher/login-queue/")));
+}
+
+#[test]
+fn status_prints_region_state_and_version() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+
+    let output = server.run(home.path(), &["status"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("region 1 — Mock Server: Online, v2.0.0\n"));
+
+    {
+        let mut state = server.state();
+        state.region_id = 9;
+        state.message_code = 3;
+        state.update_in_progress = true;
+    }
+    let output = server.run(home.path(), &["status"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output)
+        .contains("region 9 — Mock Server: Updating, v2.0.0 (message unknown(3))\n"));
+}
+
+#[test]
+fn status_prints_machine_readable_formats() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+
+    let output = server.run(home.path(), &["status", "--format", "json"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let status: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(status["regionId"], 1);
+    assert_eq!(status["state"], "online");
+    assert_eq!(status["gameVersion"], "2.0.0");
+    assert_eq!(status["messageCode"], 0);
+
+    let output = server.run(home.path(), &["status", "--format", "table"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("State    Online\n"));
+    assert!(stdout(&output).contains("Version  2.0.0\n"));
+
+    server.state().update_in_progress = true;
+    let output = server.run(home.path(), &["status", "--format", "waybar"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let block: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(block["text"], "Mock Server Updating");
+    assert_eq!(block["tooltip"], "region 1 — Mock Server: Updating, v2.0.0");
+    assert_eq!(block["class"], "updating");
+
+    server.state().online = false;
+    server.state().update_in_progress = false;
+    let output = server.run(home.path(), &["status", "--format", "i3blocks"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "region 1 — Mock Server: Offline, v2.0.0\nMock Server Offline\n#FF0000\n"
+    );
+}
+
+#[test]
+fn endpoints_can_be_set_on_the_command_line() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+
+    let output = server
+        .launcher(home.path())
+        .env_remove("SSO_LAUNCHER_PROXY_URL")
+        .env_remove("SSO_LAUNCHER_FILES_URL")
+        .env("SSO_PASSWORD", PASSWORD)
+        .args(["--email", EMAIL, "--launcher-proxy-url", &server.url])
+        .args(["--launcher-files-url", &format!("{}launcher-files", server.url)])
+        .arg("status")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Mock Server"));
+    assert!(server
+        .state()
+        .requests
+        .contains(&"GET /launcher-files/latest/latest.yml".to_string()));
+}
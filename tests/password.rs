@@ -0,0 +1,127 @@
+mod common;
+
+use common::{stderr, MockServer, EMAIL, PASSWORD};
+use std::fs;
+use std::io::Write;
+use std::process::{Output, Stdio};
+
+/// Runs `status` with the mock email, `args` selecting the password source and `stdin` piped in.
+fn status(server: &MockServer, home: &std::path::Path, args: &[&str], stdin: &str) -> Output {
+    let mut child = server
+        .launcher(home)
+        .args(["--email", EMAIL])
+        .args(args)
+        .arg("status")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn password_argument_warns() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+
+    let output = status(&server, home.path(), &["--password", PASSWORD], "");
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("visible in shell history"));
+}
+
+#[test]
+fn password_env_wins_over_argument() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+
+    let output = server
+        .launcher(home.path())
+        .env("SSO_PASSWORD", PASSWORD)
+        .args(["--email", EMAIL, "--password", "wrong", "status"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!stderr(&output).contains("visible in shell history"));
+}
+
+#[test]
+fn password_is_read_from_stdin() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+
+    let output = status(
+        &server,
+        home.path(),
+        &["--password-stdin"],
+        &format!("{}\n", PASSWORD),
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!stderr(&output).contains("warning"));
+}
+
+#[test]
+fn password_is_read_from_file() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+    let file = home.path().join("password");
+    fs::write(&file, format!("{}\r\n", PASSWORD)).unwrap();
+
+    let output = status(
+        &server,
+        home.path(),
+        &["--password-file", file.to_str().unwrap()],
+        "",
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+}
+
+#[cfg(unix)]
+#[test]
+fn password_is_read_from_command() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+
+    let command = format!("echo {}", PASSWORD);
+    let output = status(&server, home.path(), &["--password-command", &command], "");
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    // The password is only read when logging in, the cached session would skip it
+    let output = status(
+        &server,
+        home.path(),
+        &["--no-session-cache", "--password-command", "exit 1"],
+        "",
+    );
+    assert_eq!(output.status.code(), Some(14), "{}", stderr(&output));
+}
+
+#[test]
+fn password_sources_are_exclusive() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+
+    let output = status(
+        &server,
+        home.path(),
+        &["--password", PASSWORD, "--password-stdin"],
+        "",
+    );
+    assert_eq!(output.status.code(), Some(2), "{}", stderr(&output));
+}
+
+#[test]
+fn missing_password_without_terminal_fails() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+
+    let output = status(&server, home.path(), &[], "");
+    assert_eq!(output.status.code(), Some(14), "{}", stderr(&output));
+    assert!(server.state().requests.is_empty());
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use common::{stderr, MockServer};
+use std::fs;
+use std::path::Path;
+
+const AUTH_REQUEST: &str = "POST /launcher/auth/";
+
+fn logins(server: &MockServer) -> usize {
+    server
+        .state()
+        .requests
+        .iter()
+        .filter(|request| *request == AUTH_REQUEST)
+        .count()
+}
+
+fn session_files(home: &Path) -> Vec<std::path::PathBuf> {
+    fs::read_dir(home.join("cache/sessions"))
+        .map(|entries| entries.map(|entry| entry.unwrap().path()).collect())
+        .unwrap_or_default()
+}
+
+#[test]
+fn status_reuses_the_cached_session() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+
+    for _ in 0..2 {
+        let output = server.run(home.path(), &["status"]);
+        assert!(output.status.success(), "{}", stderr(&output));
+    }
+    assert_eq!(logins(&server), 1);
+
+    let sessions = session_files(home.path());
+    assert_eq!(sessions.len(), 1);
+    let session = fs::read_to_string(&sessions[0]).unwrap();
+    assert!(session.contains(common::LAUNCHER_HASH));
+    assert!(!session.contains(common::PASSWORD));
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&sessions[0]).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn existing_session_file_is_made_private() {
+    use std::os::unix::fs::PermissionsExt;
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+
+    let output = server.run(home.path(), &["status"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let session = &session_files(home.path())[0];
+    fs::set_permissions(session, fs::Permissions::from_mode(0o644)).unwrap();
+    server.state().launcher_hash = "rotated-launcher-hash".to_string();
+
+    let output = server.run(home.path(), &["status"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let mode = fs::metadata(session).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+}
+
+#[test]
+fn rejected_session_logs_in_again() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+
+    let output = server.run(home.path(), &["status"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    server.state().launcher_hash = "rotated-launcher-hash".to_string();
+
+    let output = server.run(home.path(), &["status"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(logins(&server), 2);
+
+    let session = fs::read_to_string(&session_files(home.path())[0]).unwrap();
+    assert!(session.contains("rotated-launcher-hash"));
+}
+
+#[test]
+fn no_session_cache_always_logs_in() {
+    let server = MockServer::start();
+    let home = tempfile::tempdir().unwrap();
+
+    for _ in 0..2 {
+        let output = server.run(home.path(), &["--no-session-cache", "status"]);
+        assert!(output.status.success(), "{}", stderr(&output));
+    }
+    assert_eq!(logins(&server), 2);
+    assert!(session_files(home.path()).is_empty());
+}
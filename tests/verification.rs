@@ -0,0 +1,61 @@
+mod common;
+
+use common::{stderr, stdout, MockServer, MockState};
+
+fn challenged_server() -> MockServer {
+    MockServer::start_with(MockState {
+        verification_code: Some("424242".to_string()),
+        ..MockState::default()
+    })
+}
+
+fn logins(server: &MockServer) -> usize {
+    server
+        .state()
+        .requests
+        .iter()
+        .filter(|request| *request == "POST /launcher/auth/")
+        .count()
+}
+
+#[test]
+fn code_answers_the_challenge() {
+    let server = challenged_server();
+    let home = tempfile::tempdir().unwrap();
+
+    let output = server.run(home.path(), &["--code", "424242", "status"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Mock Server"));
+    assert_eq!(logins(&server), 2);
+}
+
+#[cfg(unix)]
+#[test]
+fn code_command_answers_the_challenge() {
+    let server = challenged_server();
+    let home = tempfile::tempdir().unwrap();
+
+    let output = server.run(home.path(), &["--code-command", "echo 424242", "status"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+}
+
+#[test]
+fn wrong_code_fails_verification() {
+    let server = challenged_server();
+    let home = tempfile::tempdir().unwrap();
+
+    let output = server.run(home.path(), &["--code", "000000", "status"]);
+    assert_eq!(output.status.code(), Some(20), "{}", stderr(&output));
+    assert!(stderr(&output).contains("wrong or expired"));
+}
+
+#[test]
+fn missing_code_without_terminal_fails_verification() {
+    let server = challenged_server();
+    let home = tempfile::tempdir().unwrap();
+
+    let output = server.run(home.path(), &["status"]);
+    assert_eq!(output.status.code(), Some(20), "{}", stderr(&output));
+    assert!(stderr(&output).contains("--code"));
+    assert_eq!(logins(&server), 1);
+}